@@ -0,0 +1,169 @@
+use bevy::prelude::*;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum DasCharge {
+    // a held direction keeps its charge when the next piece spawns
+    #[default]
+    Preserve,
+    // every new piece has to charge DAS from zero
+    ResetOnSpawn,
+}
+
+impl DasCharge {
+    pub fn toggled(self) -> Self {
+        match self {
+            DasCharge::Preserve => DasCharge::ResetOnSpawn,
+            DasCharge::ResetOnSpawn => DasCharge::Preserve,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            DasCharge::Preserve => "keep",
+            DasCharge::ResetOnSpawn => "reset on spawn",
+        }
+    }
+}
+
+#[derive(Resource, Debug, Copy, Clone, PartialEq)]
+pub struct InputSettings {
+    pub das_ms: f32,
+    pub arr_ms: f32,
+    pub soft_drop_factor: f32,
+    pub das_cut_ms: f32,
+    pub das_charge: DasCharge,
+}
+
+impl Default for InputSettings {
+    fn default() -> Self {
+        InputSettings {
+            das_ms: 167.0,
+            arr_ms: 33.0,
+            soft_drop_factor: 20.0,
+            das_cut_ms: 0.0,
+            das_charge: DasCharge::Preserve,
+        }
+    }
+}
+
+// Step counts returned by `AutoRepeat` use `usize::MAX` for "as far as the
+// piece can go", which is what ARR 0 and instant soft drop ask for.
+#[derive(Resource, Debug, Default)]
+pub struct AutoRepeat {
+    // `Game::spawns` when the current piece came in
+    piece: Option<u32>,
+    direction: isize,
+    charge_ms: f32,
+    repeat_ms: f32,
+    cut_ms: f32,
+    soft_drop_ms: f32,
+}
+
+impl AutoRepeat {
+    pub fn track(&mut self, piece: u32, settings: &InputSettings) {
+        if self.piece != Some(piece) {
+            self.piece = Some(piece);
+            if settings.das_charge == DasCharge::ResetOnSpawn {
+                self.charge_ms = 0.0;
+                self.repeat_ms = 0.0;
+            }
+        }
+    }
+
+    pub fn cut(&mut self, settings: &InputSettings) {
+        self.cut_ms = settings.das_cut_ms;
+    }
+
+    pub fn direction(&self) -> isize {
+        self.direction
+    }
+
+    pub fn shift(
+        &mut self,
+        held_left: bool,
+        held_right: bool,
+        just_pressed: Option<isize>,
+        dt_ms: f32,
+        settings: &InputSettings,
+    ) -> usize {
+        // the cut runs out whether or not a direction is held
+        let cutting = self.cut_ms > 0.0;
+        self.cut_ms = (self.cut_ms - dt_ms).max(0.0);
+
+        if let Some(direction) = just_pressed {
+            self.direction = direction;
+            self.charge_ms = 0.0;
+            self.repeat_ms = 0.0;
+            return 1;
+        }
+
+        let held = |direction: isize| if direction < 0 { held_left } else { held_right };
+        if self.direction == 0 || !held(self.direction) {
+            // fall back to the other key if it is still down
+            match [-1, 1].into_iter().find(|&direction| held(direction)) {
+                Some(direction) => {
+                    self.direction = direction;
+                    self.charge_ms = 0.0;
+                    self.repeat_ms = 0.0;
+                }
+                None => {
+                    self.direction = 0;
+                    self.charge_ms = 0.0;
+                    return 0;
+                }
+            }
+        }
+
+        if cutting {
+            return 0;
+        }
+
+        let was_charged = self.charge_ms >= settings.das_ms;
+        let overflow = self.charge_ms + dt_ms - settings.das_ms;
+        self.charge_ms = (self.charge_ms + dt_ms).min(settings.das_ms);
+        if overflow < 0.0 {
+            return 0;
+        }
+        if settings.arr_ms <= 0.0 {
+            return usize::MAX;
+        }
+
+        let mut steps = 0;
+        if was_charged {
+            self.repeat_ms += dt_ms;
+        } else {
+            self.repeat_ms = overflow;
+            steps += 1;
+        }
+        let repeats = (self.repeat_ms / settings.arr_ms) as usize;
+        self.repeat_ms -= repeats as f32 * settings.arr_ms;
+        steps + repeats
+    }
+
+    pub fn soft_drop(
+        &mut self,
+        held: bool,
+        just_pressed: bool,
+        dt_ms: f32,
+        gravity_ms: f32,
+        settings: &InputSettings,
+    ) -> usize {
+        if !held {
+            self.soft_drop_ms = 0.0;
+            return 0;
+        }
+        if just_pressed {
+            self.soft_drop_ms = 0.0;
+            return 1;
+        }
+
+        if settings.soft_drop_factor.is_infinite() {
+            return usize::MAX;
+        }
+        let interval = gravity_ms / settings.soft_drop_factor.max(1.0);
+        self.soft_drop_ms += dt_ms;
+        let steps = (self.soft_drop_ms / interval) as usize;
+        self.soft_drop_ms -= steps as f32 * interval;
+        steps
+    }
+}
@@ -0,0 +1,138 @@
+use bevy::input::ButtonInput;
+use bevy::input::keyboard::KeyCode;
+use bevy::prelude::*;
+use std::collections::{HashSet, VecDeque};
+
+use crate::game_core::board::{GameMap, PieceType, mask_rows};
+use crate::game_core::piece::{Piece, Position};
+use crate::game_core::spawn_position;
+use crate::game_core::srs::{Rotation, try_rotate};
+use crate::resources::{CurrentGame, LockEvent};
+use crate::stats::GameStats;
+use crate::systems::input::{
+    MOVE_LEFT_KEY, MOVE_RIGHT_KEY, ROTATE_CCW_KEYS, ROTATE_CW_KEYS, SOFT_DROP_KEY,
+};
+
+pub const FINESSE_KEYS: [KeyCode; 6] = [
+    MOVE_LEFT_KEY,
+    MOVE_RIGHT_KEY,
+    ROTATE_CW_KEYS[0],
+    ROTATE_CW_KEYS[1],
+    ROTATE_CCW_KEYS[0],
+    ROTATE_CCW_KEYS[1],
+];
+
+// Finesse inputs spent on the active piece so far. A held DAS charge is one
+// input, the same as a tap.
+#[derive(Resource, Debug, Default)]
+pub struct PieceInputs {
+    // `Game::spawns` when the current piece came in
+    piece: Option<u32>,
+    pub count: u32,
+    pub soft_dropped: bool,
+}
+
+// The piece's cells as row masks, moved up so the first row is occupied.
+// Rotation states that cover the same cells compare equal.
+pub fn footprint(mask: u16, x: isize) -> [u32; 4] {
+    let mut rows = mask_rows(mask).map(|bits| if x >= 0 { bits << x } else { bits >> -x });
+    let first = rows.iter().position(|&bits| bits != 0).unwrap_or(0);
+    rows.rotate_left(first);
+    rows
+}
+
+// The fewest taps, wall charges and rotations that take a fresh piece from
+// its spawn to the given placement on an empty board as wide as `game_map`.
+pub fn minimum_inputs(
+    game_map: &GameMap,
+    piece_type: PieceType,
+    position: &Position,
+    rotation: usize,
+) -> Option<u32> {
+    let empty = GameMap::with_buffer(
+        game_map.width(),
+        game_map.visible_rows(),
+        game_map.buffer_rows(),
+    );
+    let piece = Piece::from(piece_type);
+    let target = footprint(piece.states[rotation], position.x);
+    let start = spawn_position(&empty, &piece);
+
+    let mut seen = HashSet::from([(start.x, 0)]);
+    let mut queue = VecDeque::from([(start, 0, 0)]);
+    while let Some((at, state, inputs)) = queue.pop_front() {
+        if footprint(piece.states[state], at.x) == target {
+            return Some(inputs);
+        }
+        let turned = Piece {
+            current_state: state,
+            ..piece
+        };
+        let mut next = Vec::new();
+        for direction in [-1, 1] {
+            if empty.collides(&piece, state, at.x + direction, at.y) {
+                continue;
+            }
+            // a tap moves one column, a full charge runs into the wall
+            let mut x = at.x + direction;
+            next.push((Position { x, y: at.y }, state));
+            while !empty.collides(&piece, state, x + direction, at.y) {
+                x += direction;
+            }
+            next.push((Position { x, y: at.y }, state));
+        }
+        for rotation in [Rotation::Clockwise, Rotation::CounterClockwise] {
+            if let Some(result) = try_rotate(&turned, &at, rotation, &empty) {
+                next.push((result.position, result.state));
+            }
+        }
+        for (position, state) in next {
+            if seen.insert((position.x, state)) {
+                queue.push_back((position, state, inputs + 1));
+            }
+        }
+    }
+    None
+}
+
+// Counts a fault for each piece that took more inputs than it needed.
+// Soft-dropped pieces are skipped since tucks and spins need the extra moves.
+pub fn judge_finesse(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    game: Res<CurrentGame>,
+    mut inputs: ResMut<PieceInputs>,
+    mut locked_events: EventReader<LockEvent>,
+    mut stats: ResMut<GameStats>,
+) {
+    inputs.count += keyboard_input
+        .get_just_pressed()
+        .filter(|key| FINESSE_KEYS.contains(key))
+        .count() as u32;
+    if keyboard_input.just_pressed(SOFT_DROP_KEY) {
+        inputs.soft_dropped = true;
+    }
+
+    for event in locked_events.read() {
+        if !inputs.soft_dropped
+            && let Some(minimum) = minimum_inputs(
+                &game.game_map,
+                event.piece_type,
+                &event.position,
+                event.rotation,
+            )
+            && inputs.count > minimum
+        {
+            stats.finesse_faults += 1;
+        }
+        *inputs = PieceInputs::default();
+    }
+
+    // a new piece, from a lock, a hold or a new game, starts from zero
+    let active = game.active_piece().map(|_| game.spawns());
+    if inputs.piece != active {
+        *inputs = PieceInputs {
+            piece: active,
+            ..default()
+        };
+    }
+}
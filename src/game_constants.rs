@@ -1,12 +1,22 @@
 pub const TITLE: &str = "Tetris in Rust";
 
 pub const NUM_BLOCKS_X: usize = 10;
-pub const NUM_BLOCKS_Y: usize = 18;
+pub const NUM_BLOCKS_Y: usize = 20;
+// Hidden rows above the visible field; the full matrix is 40 rows tall.
+pub const BUFFER_ROWS: usize = 20;
+pub const MATRIX_ROWS: usize = NUM_BLOCKS_Y + BUFFER_ROWS;
 
 pub const TEXTURE_SIZE: u32 = 32;
 
-pub const WIDTH: u32 = NUM_BLOCKS_X as u32 * TEXTURE_SIZE;
-pub const HEIGHT: u32 = NUM_BLOCKS_Y as u32 * TEXTURE_SIZE;
+pub const SIDE_PANEL_WIDTH: u32 = 6 * TEXTURE_SIZE;
+// The hold preview and the HUD under it need this many rows, so the window
+// never gets shorter than that on short boards.
+pub const SIDE_PANEL_ROWS: usize = 12;
+// Each next preview takes three rows of its panel.
+pub const PREVIEW_ROWS: usize = 3;
+// Taller boards get a smaller window and the camera scales to fit.
+pub const MAX_WINDOW_HEIGHT: f32 = 960.0;
 
-pub const NUM_LEVELS: usize = 10;
-pub const LEVEL_TIMES: [usize; NUM_LEVELS] = [3000, 850, 700, 600, 500, 400, 300, 250, 221, 190];
+pub const MIN_BOARD_WIDTH: usize = 4;
+pub const MAX_BOARD_WIDTH: usize = 32;
+pub const MIN_BOARD_HEIGHT: usize = 4;
@@ -0,0 +1,317 @@
+use super::color::GameColor;
+use super::piece::{Piece, Position};
+use crate::game_constants::{
+    BUFFER_ROWS, MAX_BOARD_WIDTH, MIN_BOARD_HEIGHT, MIN_BOARD_WIDTH, NUM_BLOCKS_X, NUM_BLOCKS_Y,
+};
+use std::time::Duration;
+
+// Size of the visible field; the hidden buffer above it is always added.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct BoardSize {
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Default for BoardSize {
+    fn default() -> Self {
+        BoardSize {
+            width: NUM_BLOCKS_X,
+            height: NUM_BLOCKS_Y,
+        }
+    }
+}
+
+impl BoardSize {
+    pub fn new(width: usize, height: usize) -> Self {
+        BoardSize {
+            width: width.clamp(MIN_BOARD_WIDTH, MAX_BOARD_WIDTH),
+            height: height.max(MIN_BOARD_HEIGHT),
+        }
+    }
+
+    pub fn game_map(&self) -> GameMap {
+        GameMap::new(self.width, self.height)
+    }
+}
+
+pub type PieceMatrix = [[Presence; 4]; 4];
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum PieceType {
+    L,
+    J,
+    S,
+    Z,
+    #[default]
+    T,
+    I,
+    O,
+}
+
+impl PieceType {
+    pub const ALL: [PieceType; 7] = [
+        PieceType::L,
+        PieceType::J,
+        PieceType::S,
+        PieceType::Z,
+        PieceType::T,
+        PieceType::I,
+        PieceType::O,
+    ];
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Presence {
+    No,
+    Yes(GameColor),
+}
+
+// Occupancy is one bitmask per row with column x at bit x, so collisions
+// and line clears are a handful of mask operations. Colors live in a
+// separate layer and are only meaningful where the occupancy bit is set.
+// Row 0 is the top of the hidden buffer; the visible field starts at
+// `buffer_rows`. Each cell also remembers when it was locked, on the game
+// clock, so the stack can fade out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameMap {
+    width: usize,
+    buffer_rows: usize,
+    rows: Vec<u32>,
+    colors: Vec<Vec<GameColor>>,
+    locked_at: Vec<Vec<Duration>>,
+}
+
+impl Default for GameMap {
+    fn default() -> Self {
+        GameMap::new(NUM_BLOCKS_X, NUM_BLOCKS_Y)
+    }
+}
+
+// Piece columns are shifted past this many wall bits on the left so that
+// positions left of the board still land inside the mask.
+const WALL_BITS: isize = 4;
+
+impl GameMap {
+    pub fn new(width: usize, visible_rows: usize) -> Self {
+        Self::with_buffer(width, visible_rows, BUFFER_ROWS)
+    }
+
+    pub fn with_buffer(width: usize, visible_rows: usize, buffer_rows: usize) -> Self {
+        assert!(width > 0 && width <= 32, "board width must be 1..=32");
+        let height = visible_rows + buffer_rows;
+        GameMap {
+            width,
+            buffer_rows,
+            rows: vec![0; height],
+            colors: vec![vec![GameColor::default(); width]; height],
+            locked_at: vec![vec![Duration::ZERO; width]; height],
+        }
+    }
+
+    // Builds a map with no hidden rows, mostly for setting up tests.
+    pub fn from_cells(cells: &[Vec<Presence>]) -> Self {
+        let width = cells.first().map_or(NUM_BLOCKS_X, Vec::len);
+        let mut game_map = GameMap::with_buffer(width, cells.len(), 0);
+        for (y, row) in cells.iter().enumerate() {
+            for (x, &cell) in row.iter().enumerate() {
+                game_map.set(x, y, cell);
+            }
+        }
+        game_map
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn buffer_rows(&self) -> usize {
+        self.buffer_rows
+    }
+
+    pub fn visible_rows(&self) -> usize {
+        self.height() - self.buffer_rows
+    }
+
+    pub fn full_row_mask(&self) -> u32 {
+        u32::MAX >> (32 - self.width)
+    }
+
+    pub fn row_mask(&self, y: usize) -> u32 {
+        self.rows[y]
+    }
+
+    pub fn is_row_full(&self, y: usize) -> bool {
+        self.rows[y] == self.full_row_mask()
+    }
+
+    pub fn is_occupied(&self, x: usize, y: usize) -> bool {
+        self.rows[y] & (1 << x) != 0
+    }
+
+    pub fn cell(&self, x: usize, y: usize) -> Presence {
+        if self.is_occupied(x, y) {
+            Presence::Yes(self.colors[y][x])
+        } else {
+            Presence::No
+        }
+    }
+
+    pub fn locked_at(&self, x: usize, y: usize) -> Duration {
+        self.locked_at[y][x]
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, cell: Presence) {
+        match cell {
+            Presence::Yes(color) => {
+                self.rows[y] |= 1 << x;
+                self.colors[y][x] = color;
+            }
+            Presence::No => self.rows[y] &= !(1 << x),
+        }
+    }
+
+    // True when a 4x4 piece mask at (x, y) hits the stack, a wall or the
+    // floor. Rows above the top of the board are open.
+    pub fn overlaps(&self, mask: u16, x: isize, y: isize) -> bool {
+        let shift = x + WALL_BITS;
+        let walls = ((1u64 << WALL_BITS) - 1) | (u64::MAX << (self.width as isize + WALL_BITS));
+        for (dy, bits) in mask_rows(mask).into_iter().enumerate() {
+            if bits == 0 {
+                continue;
+            }
+            let row = y + dy as isize;
+            if row >= self.height() as isize || shift < 0 || x >= self.width as isize {
+                return true;
+            }
+            let stack = if row >= 0 {
+                (self.rows[row as usize] as u64) << WALL_BITS
+            } else {
+                0
+            };
+            if ((bits as u64) << shift) & (walls | stack) != 0 {
+                return true;
+            }
+        }
+        false
+    }
+
+    // The one collision rule for pieces: walls, the floor and the stack are
+    // solid, while rows above the top of the board are open so pieces can
+    // spawn and kick into the hidden area.
+    pub fn collides(&self, piece: &Piece, state: usize, x: isize, y: isize) -> bool {
+        self.overlaps(piece.states[state], x, y)
+    }
+
+    pub fn lock_piece(&mut self, piece: &Piece, position: &Position) {
+        self.place(
+            piece.states[piece.current_state],
+            position.x,
+            position.y,
+            piece.color,
+        );
+    }
+
+    // The board cells covered by a piece mask, leaving out any that fall
+    // outside.
+    fn mask_cells(&self, mask: u16, x: isize, y: isize) -> Vec<(usize, usize)> {
+        let mut cells = Vec::new();
+        for (dy, bits) in mask_rows(mask).into_iter().enumerate() {
+            for dx in 0..4 {
+                let (cx, cy) = (x + dx, y + dy as isize);
+                if bits & (1 << dx) != 0
+                    && cx >= 0
+                    && cx < self.width as isize
+                    && cy >= 0
+                    && cy < self.height() as isize
+                {
+                    cells.push((cx as usize, cy as usize));
+                }
+            }
+        }
+        cells
+    }
+
+    // Writes the cells of a piece mask, dropping any that fall outside.
+    pub fn place(&mut self, mask: u16, x: isize, y: isize, color: GameColor) {
+        for (cx, cy) in self.mask_cells(mask, x, y) {
+            self.set(cx, cy, Presence::Yes(color));
+        }
+    }
+
+    // Records the lock time of the cells under a piece mask.
+    pub fn stamp(&mut self, mask: u16, x: isize, y: isize, at: Duration) {
+        for (cx, cy) in self.mask_cells(mask, x, y) {
+            self.locked_at[cy][cx] = at;
+        }
+    }
+
+    // Pushes rows in under the stack, last one at the bottom, raising
+    // everything above. Returns false if occupied cells went off the top.
+    pub fn insert_bottom_rows(&mut self, rows: &[u32], color: GameColor) -> bool {
+        let mut kept = true;
+        for &mask in rows {
+            kept &= self.rows.remove(0) == 0;
+            self.colors.remove(0);
+            self.locked_at.remove(0);
+            self.rows.push(mask & self.full_row_mask());
+            self.colors.push(vec![color; self.width]);
+            self.locked_at.push(vec![Duration::ZERO; self.width]);
+        }
+        kept
+    }
+
+    // A full row except for one hole.
+    pub fn row_with_hole(&self, hole: usize) -> u32 {
+        self.full_row_mask() & !(1 << hole)
+    }
+
+    // Removes the given rows and drops everything above them.
+    pub fn clear_rows(&mut self, rows: &[usize]) {
+        for &y in rows.iter().rev() {
+            self.rows.remove(y);
+            self.colors.remove(y);
+            self.locked_at.remove(y);
+        }
+        for _ in rows {
+            self.rows.insert(0, 0);
+            self.colors
+                .insert(0, vec![GameColor::default(); self.width]);
+            self.locked_at.insert(0, vec![Duration::ZERO; self.width]);
+        }
+    }
+}
+
+pub fn full_rows(game_map: &GameMap) -> Vec<usize> {
+    (0..game_map.height())
+        .filter(|&y| game_map.is_row_full(y))
+        .collect()
+}
+
+// True when locking leaves nothing behind once the full rows are cleared.
+pub fn is_perfect_clear(game_map: &GameMap) -> bool {
+    let full = game_map.full_row_mask();
+    let mut any_full = false;
+    for y in 0..game_map.height() {
+        match game_map.row_mask(y) {
+            0 => {}
+            mask if mask == full => any_full = true,
+            _ => return false,
+        }
+    }
+    any_full
+}
+
+// Splits a 4x4 piece mask into row masks with column x at bit x, matching
+// the board layout.
+pub fn mask_rows(mask: u16) -> [u32; 4] {
+    let mut rows = [0; 4];
+    for (row, bits) in rows.iter_mut().enumerate() {
+        let nibble = (mask >> (12 - row * 4)) & 0xF;
+        *bits = (nibble.reverse_bits() >> 12) as u32;
+    }
+    rows
+}
@@ -0,0 +1,14 @@
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum GameColor {
+    #[default]
+    Red,
+    Green,
+    Blue,
+    Yellow,
+    Cyan,
+    Orange,
+    Purple,
+    Gray,
+    Pink,
+    DarkGray,
+}
@@ -0,0 +1,57 @@
+// Time between a lock and the next piece (ARE), plus extra time for the
+// cleared rows to vanish. Both are zero unless a mode asks for them.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct EntryDelay {
+    pub are: f32,
+    pub line_clear: f32,
+}
+
+impl EntryDelay {
+    pub fn is_instant(&self) -> bool {
+        self.are <= 0.0 && self.line_clear <= 0.0
+    }
+}
+
+// The wait for the next piece. Full rows stay on the board for the line
+// clear part, then ARE runs.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct EntryTimer {
+    waiting: bool,
+    clear_left: f32,
+    are_left: f32,
+}
+
+impl EntryTimer {
+    pub fn start(&mut self, delay: &EntryDelay, lines_cleared: bool) {
+        *self = EntryTimer {
+            waiting: true,
+            clear_left: if lines_cleared { delay.line_clear } else { 0.0 },
+            are_left: delay.are,
+        };
+    }
+
+    pub fn is_waiting(&self) -> bool {
+        self.waiting
+    }
+
+    pub fn is_clearing(&self) -> bool {
+        self.waiting && self.clear_left > 0.0
+    }
+
+    // True once the wait is over and the next piece should come in.
+    pub fn tick(&mut self, dt: f32) -> bool {
+        if !self.waiting {
+            return false;
+        }
+        if self.clear_left > 0.0 {
+            self.clear_left -= dt;
+            return false;
+        }
+        self.are_left -= dt;
+        if self.are_left > 0.0 {
+            return false;
+        }
+        self.waiting = false;
+        true
+    }
+}
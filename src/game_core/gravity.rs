@@ -0,0 +1,76 @@
+pub const FRAME_RATE: f32 = 60.0;
+pub const MAX_GRAVITY: f32 = 20.0;
+
+// Guideline curve: (0.8 - (level - 1) * 0.007) ^ (level - 1) seconds per row.
+pub fn seconds_per_row(level: u32) -> f32 {
+    let n = level.max(1) as f32 - 1.0;
+    (0.8 - n * 0.007).max(0.0).powf(n)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gravity {
+    pub seconds_per_row: f32,
+    rows: f32,
+    // set by modes that keep one speed instead of following `Level`
+    fixed_level: Option<u32>,
+}
+
+impl Gravity {
+    pub fn new(seconds_per_row: f32) -> Self {
+        Gravity {
+            seconds_per_row,
+            rows: 0.0,
+            fixed_level: None,
+        }
+    }
+
+    pub fn for_level(level: u32) -> Self {
+        Self::new(seconds_per_row(level))
+    }
+
+    pub fn fix_level(&mut self, level: u32) {
+        self.seconds_per_row = seconds_per_row(level);
+        self.fixed_level = Some(level);
+    }
+
+    // Level ups only speed the piece up when no fixed level was set.
+    pub fn follow_level(&mut self, level: u32) {
+        if self.fixed_level.is_none() {
+            self.seconds_per_row = seconds_per_row(level);
+        }
+    }
+
+    pub fn fixed_level(&self) -> Option<u32> {
+        self.fixed_level
+    }
+
+    // Rows per frame at 60 fps, the "G" unit.
+    pub fn g(&self) -> f32 {
+        1.0 / (self.seconds_per_row * FRAME_RATE)
+    }
+
+    pub fn is_instant(&self) -> bool {
+        self.g() >= MAX_GRAVITY
+    }
+
+    // Rows the piece should fall this frame; `usize::MAX` at 20G.
+    pub fn advance(&mut self, dt: f32) -> usize {
+        if self.is_instant() {
+            return usize::MAX;
+        }
+        self.rows += dt / self.seconds_per_row;
+        let rows = self.rows.floor();
+        self.rows -= rows;
+        rows as usize
+    }
+
+    pub fn reset_progress(&mut self) {
+        self.rows = 0.0;
+    }
+}
+
+impl Default for Gravity {
+    fn default() -> Self {
+        Self::for_level(1)
+    }
+}
@@ -0,0 +1,63 @@
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct LockDelay {
+    pub delay: f32,
+    pub max_resets: u32,
+}
+
+impl Default for LockDelay {
+    fn default() -> Self {
+        LockDelay {
+            delay: 0.5,
+            max_resets: 15,
+        }
+    }
+}
+
+// Tracks lock down for the active piece. Moves and rotations made while the
+// piece rests on the stack restart the timer until the reset budget runs
+// out; reaching a new lowest row refills the budget.
+#[derive(Debug, Default)]
+pub struct LockTimer {
+    lowest_y: isize,
+    elapsed: f32,
+    resets: u32,
+    grounded: bool,
+}
+
+impl LockTimer {
+    pub fn reset(&mut self, y: isize) {
+        *self = LockTimer {
+            lowest_y: y,
+            ..Default::default()
+        };
+    }
+
+    pub fn observe(&mut self, y: isize) {
+        if y > self.lowest_y {
+            self.lowest_y = y;
+            self.elapsed = 0.0;
+            self.resets = 0;
+        }
+    }
+
+    pub fn on_move(&mut self, lock_delay: &LockDelay) {
+        if self.grounded && self.resets < lock_delay.max_resets {
+            self.elapsed = 0.0;
+            self.resets += 1;
+        }
+    }
+
+    pub fn tick(&mut self, dt: f32, grounded: bool, lock_delay: &LockDelay) -> bool {
+        self.grounded = grounded;
+        if !grounded {
+            self.elapsed = 0.0;
+            return false;
+        }
+        self.elapsed += dt;
+        self.elapsed >= lock_delay.delay || self.resets >= lock_delay.max_resets
+    }
+
+    pub fn resets(&self) -> u32 {
+        self.resets
+    }
+}
@@ -0,0 +1,483 @@
+// Rules of the game without the ECS: `Game` can be stepped directly by bots,
+// servers and tests, and the Bevy systems only feed it input and time.
+
+pub mod board;
+pub mod color;
+pub mod entry_delay;
+pub mod gravity;
+pub mod lock_delay;
+pub mod piece;
+pub mod progression;
+pub mod randomizer;
+pub mod scoring;
+pub mod srs;
+pub mod tspin;
+
+use std::time::Duration;
+
+use board::{BoardSize, GameMap, PieceType, full_rows, is_perfect_clear, mask_rows};
+use color::GameColor;
+use entry_delay::{EntryDelay, EntryTimer};
+use gravity::Gravity;
+use lock_delay::{LockDelay, LockTimer};
+use piece::{Piece, Position};
+use progression::{Level, LevelProgression};
+use randomizer::{GameRng, NextQueue, PieceRandomizer};
+use scoring::Score;
+use srs::{Rotation, try_rotate};
+use tspin::{PieceLocked, detect_tspin};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum TopOut {
+    // the next piece overlaps the stack where it spawns
+    BlockOut,
+    // a piece locked entirely above the visible field
+    LockOut,
+    // a piece locked with some of its cells above the visible field
+    PartialLockOut,
+    // rising garbage pushed the stack past the top of the buffer
+    Overflow,
+}
+
+impl TopOut {
+    pub fn label(self) -> &'static str {
+        match self {
+            TopOut::BlockOut => "Block Out",
+            TopOut::LockOut => "Lock Out",
+            TopOut::PartialLockOut => "Partial Lock Out",
+            TopOut::Overflow => "Top Out",
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Action {
+    MoveLeft,
+    MoveRight,
+    SoftDrop,
+    HardDrop,
+    RotateClockwise,
+    RotateCounterClockwise,
+    Hold,
+}
+
+// Pieces spawn in the two rows just above the visible field, centred and
+// rounded to the left, but pulled back inside the walls on narrow boards.
+pub fn spawn_position(game_map: &GameMap, piece: &Piece) -> Position {
+    let columns = mask_rows(piece.states[piece.current_state])
+        .into_iter()
+        .fold(0, |acc, bits| acc | bits);
+    let first = columns.trailing_zeros() as isize;
+    let last = 31 - columns.leading_zeros() as isize;
+    let centred = (game_map.width() as isize - 3) / 2;
+    Position {
+        x: centred
+            .min(game_map.width() as isize - 1 - last)
+            .max(-first),
+        y: game_map.buffer_rows() as isize - 2,
+    }
+}
+
+// Checked as a piece locks: any of its cells left above the visible field
+// ends the game.
+pub fn lock_out(game_map: &GameMap, piece: &Piece, position: &Position) -> Option<TopOut> {
+    let skyline = game_map.buffer_rows() as isize;
+    let mut above = 0;
+    let mut total = 0;
+    for (dy, bits) in mask_rows(piece.states[piece.current_state])
+        .into_iter()
+        .enumerate()
+    {
+        let cells = bits.count_ones();
+        total += cells;
+        if position.y + (dy as isize) < skyline {
+            above += cells;
+        }
+    }
+    match above {
+        0 => None,
+        _ if above == total => Some(TopOut::LockOut),
+        _ => Some(TopOut::PartialLockOut),
+    }
+}
+
+pub fn drop_distance(piece: &Piece, position: &Position, game_map: &GameMap) -> isize {
+    let mut distance = 0;
+    while !game_map.collides(
+        piece,
+        piece.current_state,
+        position.x,
+        position.y + distance + 1,
+    ) {
+        distance += 1;
+    }
+    distance
+}
+
+// Writes the piece into the map and reports what the lock achieved. Full
+// rows are left in place for the caller to clear.
+pub fn place_piece(
+    game_map: &mut GameMap,
+    piece: &Piece,
+    position: &Position,
+    rotation_kick: Option<usize>,
+) -> PieceLocked {
+    let tspin = detect_tspin(piece, position, game_map, rotation_kick);
+    game_map.lock_piece(piece, position);
+    PieceLocked {
+        piece_type: piece.piece_type,
+        position: *position,
+        rotation: piece.current_state,
+        tspin,
+        lines_cleared: full_rows(game_map).len() as u32,
+        perfect_clear: is_perfect_clear(game_map),
+    }
+}
+
+pub struct Game {
+    pub game_map: GameMap,
+    pub next_queue: NextQueue,
+    pub randomizer: PieceRandomizer,
+    pub rng: GameRng,
+    pub score: Score,
+    pub level: Level,
+    // `None` leaves the level to the mode
+    pub progression: Option<LevelProgression>,
+    pub gravity: Gravity,
+    pub lock_delay: LockDelay,
+    pub entry_delay: EntryDelay,
+    pub pieces_placed: u32,
+    pub lines_cleared: u32,
+    // Garbage sits in one block at the bottom of the board; these count the
+    // rows still there and the ones cleared so far.
+    pub garbage_rows: usize,
+    pub garbage_cleared: u32,
+    active: Option<(Piece, Position)>,
+    hold: Option<PieceType>,
+    can_hold: bool,
+    lock_timer: LockTimer,
+    entry_timer: EntryTimer,
+    rotation_kick: Option<usize>,
+    top_out: Option<TopOut>,
+    started: bool,
+    spawns: u32,
+    time: Duration,
+}
+
+impl Game {
+    pub fn new(seed: u64) -> Self {
+        Self::with_randomizer(seed, PieceRandomizer::default())
+    }
+
+    pub fn with_randomizer(seed: u64, randomizer: PieceRandomizer) -> Self {
+        Self::with_board(seed, &BoardSize::default(), randomizer)
+    }
+
+    pub fn with_board(seed: u64, board: &BoardSize, randomizer: PieceRandomizer) -> Self {
+        let mut game = Self::unstarted(seed, board, randomizer);
+        game.start();
+        game
+    }
+
+    // A game with no piece yet, so a mode can set up the board, queue and
+    // speeds before `start` spawns the first piece.
+    pub fn unstarted(seed: u64, board: &BoardSize, randomizer: PieceRandomizer) -> Self {
+        Game {
+            game_map: board.game_map(),
+            next_queue: NextQueue::default(),
+            randomizer,
+            rng: GameRng::from_seed(seed),
+            score: Score::default(),
+            level: Level::default(),
+            progression: None,
+            gravity: Gravity::default(),
+            lock_delay: LockDelay::default(),
+            entry_delay: EntryDelay::default(),
+            pieces_placed: 0,
+            lines_cleared: 0,
+            garbage_rows: 0,
+            garbage_cleared: 0,
+            active: None,
+            hold: None,
+            can_hold: true,
+            lock_timer: LockTimer::default(),
+            entry_timer: EntryTimer::default(),
+            rotation_kick: None,
+            top_out: None,
+            started: false,
+            spawns: 0,
+            time: Duration::ZERO,
+        }
+    }
+
+    // Replaces this game with a fresh, unstarted one that keeps the
+    // randomizer and preview count.
+    pub fn restart(&mut self, seed: u64, board: &BoardSize) {
+        let mut randomizer = std::mem::take(&mut self.randomizer);
+        randomizer.reset();
+        let preview_count = self.next_queue.preview_count();
+        *self = Self::unstarted(seed, board, randomizer);
+        self.next_queue = NextQueue::new(preview_count);
+    }
+
+    pub fn start(&mut self) {
+        if !self.started {
+            self.started = true;
+            self.spawn_next();
+        }
+    }
+
+    pub fn is_started(&self) -> bool {
+        self.started
+    }
+
+    pub fn active_piece(&self) -> Option<(&Piece, &Position)> {
+        self.active
+            .as_ref()
+            .map(|(piece, position)| (piece, position))
+    }
+
+    pub fn held_piece(&self) -> Option<PieceType> {
+        self.hold
+    }
+
+    pub fn can_hold(&self) -> bool {
+        self.can_hold
+    }
+
+    pub fn is_game_over(&self) -> bool {
+        self.top_out.is_some()
+    }
+
+    pub fn top_out(&self) -> Option<TopOut> {
+        self.top_out
+    }
+
+    // Pieces spawned so far, which also tells one piece from the next.
+    pub fn spawns(&self) -> u32 {
+        self.spawns
+    }
+
+    // Time stepped through `tick`, which locked cells are stamped with.
+    pub fn time(&self) -> Duration {
+        self.time
+    }
+
+    pub fn apply(&mut self, action: Action) -> Option<PieceLocked> {
+        let (mut piece, mut position) = self.active?;
+
+        match action {
+            Action::MoveLeft | Action::MoveRight => {
+                let dx = if action == Action::MoveLeft { -1 } else { 1 };
+                if self.collides(&piece, position.x + dx, position.y) {
+                    return None;
+                }
+                position.x += dx;
+                self.rotation_kick = None;
+                self.lock_timer.on_move(&self.lock_delay);
+            }
+            Action::SoftDrop => {
+                if self.collides(&piece, position.x, position.y + 1) {
+                    return None;
+                }
+                position.y += 1;
+                self.rotation_kick = None;
+                self.score.award_drop(1, false);
+                self.lock_timer.observe(position.y);
+            }
+            Action::HardDrop => {
+                let distance = drop_distance(&piece, &position, &self.game_map);
+                if distance > 0 {
+                    position.y += distance;
+                    self.rotation_kick = None;
+                    self.score.award_drop(distance as u32, true);
+                }
+                self.active = Some((piece, position));
+                return Some(self.lock());
+            }
+            Action::RotateClockwise | Action::RotateCounterClockwise => {
+                let rotation = if action == Action::RotateClockwise {
+                    Rotation::Clockwise
+                } else {
+                    Rotation::CounterClockwise
+                };
+                let result = try_rotate(&piece, &position, rotation, &self.game_map)?;
+                piece.current_state = result.state;
+                position = result.position;
+                self.rotation_kick = Some(result.kick_index);
+                self.lock_timer.on_move(&self.lock_delay);
+                self.lock_timer.observe(position.y);
+            }
+            Action::Hold => {
+                if !self.can_hold {
+                    return None;
+                }
+                let next_type = self
+                    .hold
+                    .or_else(|| self.next_queue.pop(&mut self.randomizer, &mut self.rng))?;
+                self.hold = Some(piece.piece_type);
+                self.spawn(next_type);
+                self.can_hold = false;
+                return None;
+            }
+        }
+
+        self.active = Some((piece, position));
+        None
+    }
+
+    // Steps gravity and lock down, or the wait for the next piece.
+    pub fn tick(&mut self, dt: f32) -> Option<PieceLocked> {
+        if !self.started || self.top_out.is_some() {
+            return None;
+        }
+        self.time += Duration::from_secs_f32(dt);
+
+        if self.entry_timer.is_waiting() {
+            let ready = self.entry_timer.tick(dt);
+            if !self.entry_timer.is_clearing() {
+                self.clear_full_rows();
+            }
+            if ready {
+                self.spawn_next();
+            }
+            return None;
+        }
+
+        let (piece, mut position) = self.active?;
+        for _ in 0..self.gravity.advance(dt) {
+            if self.collides(&piece, position.x, position.y + 1) {
+                // don't bank rows while resting on the stack
+                self.gravity.reset_progress();
+                break;
+            }
+            position.y += 1;
+            self.rotation_kick = None;
+        }
+        self.active = Some((piece, position));
+        self.lock_timer.observe(position.y);
+
+        let grounded = self.collides(&piece, position.x, position.y + 1);
+        if self.lock_timer.tick(dt, grounded, &self.lock_delay) {
+            Some(self.lock())
+        } else {
+            None
+        }
+    }
+
+    // Puts a new piece at the top of the board. At 20G it enters already
+    // resting on the stack.
+    pub fn spawn(&mut self, piece_type: PieceType) {
+        let piece = Piece::from(piece_type);
+        let mut position = spawn_position(&self.game_map, &piece);
+        if self.collides(&piece, position.x, position.y) {
+            self.end(TopOut::BlockOut);
+            return;
+        }
+        if self.gravity.is_instant() {
+            position.y += drop_distance(&piece, &position, &self.game_map);
+        }
+        self.active = Some((piece, position));
+        self.spawns += 1;
+        self.lock_timer.reset(position.y);
+        self.rotation_kick = None;
+    }
+
+    // Removes every full row and keeps count of the garbage among them.
+    pub fn clear_full_rows(&mut self) -> u32 {
+        let rows = full_rows(&self.game_map);
+        let garbage_top = self.game_map.height() - self.garbage_rows;
+        let garbage = rows.iter().filter(|&&y| y >= garbage_top).count();
+        self.garbage_rows -= garbage;
+        self.garbage_cleared += garbage as u32;
+        self.game_map.clear_rows(&rows);
+        rows.len() as u32
+    }
+
+    // Pushes garbage in under the stack, last row at the bottom. The rising
+    // stack carries the falling piece up with it, unless that would push it
+    // off the top of the map.
+    pub fn insert_garbage(&mut self, rows: &[u32], color: GameColor) {
+        self.garbage_rows += rows.len();
+        if !self.game_map.insert_bottom_rows(rows, color) {
+            self.end(TopOut::Overflow);
+            return;
+        }
+        let Some((piece, mut position)) = self.active else {
+            return;
+        };
+        let top = mask_rows(piece.states[piece.current_state])
+            .iter()
+            .position(|&bits| bits != 0)
+            .unwrap_or(0) as isize;
+        while self.collides(&piece, position.x, position.y) {
+            if position.y + top <= 0 {
+                self.end(TopOut::BlockOut);
+                return;
+            }
+            position.y -= 1;
+        }
+        self.active = Some((piece, position));
+    }
+
+    // Full rows stay on the board through the line clear delay, and the
+    // next piece waits out the entry delay.
+    fn lock(&mut self) -> PieceLocked {
+        let (piece, position) = self.active.take().expect("lock without an active piece");
+        let top_out = lock_out(&self.game_map, &piece, &position);
+        let locked = place_piece(&mut self.game_map, &piece, &position, self.rotation_kick);
+        self.game_map.stamp(
+            piece.states[piece.current_state],
+            position.x,
+            position.y,
+            self.time,
+        );
+
+        self.score.award_lock(
+            locked.lines_cleared,
+            locked.tspin,
+            locked.perfect_clear,
+            self.level.value,
+        );
+        if let Some(progression) = self.progression {
+            let lines = progression.lines_for(locked.lines_cleared, locked.tspin);
+            self.level.add_lines(lines, &progression);
+            self.gravity.follow_level(self.level.value);
+        }
+        self.pieces_placed += 1;
+        self.lines_cleared += locked.lines_cleared;
+        self.can_hold = true;
+
+        match top_out {
+            Some(reason) => self.end(reason),
+            None if !self.entry_delay.is_instant() => {
+                let delay = self.entry_delay;
+                self.entry_timer.start(&delay, locked.lines_cleared > 0);
+            }
+            None => {}
+        }
+        if !self.entry_timer.is_clearing() {
+            self.clear_full_rows();
+        }
+        if self.top_out.is_none() && !self.entry_timer.is_waiting() {
+            self.spawn_next();
+        }
+        locked
+    }
+
+    fn end(&mut self, reason: TopOut) {
+        self.active = None;
+        self.top_out = Some(reason);
+    }
+
+    fn collides(&self, piece: &Piece, x: isize, y: isize) -> bool {
+        self.game_map.collides(piece, piece.current_state, x, y)
+    }
+
+    // Once a finite queue is used up the mode decides how the game ends.
+    fn spawn_next(&mut self) {
+        if let Some(piece_type) = self.next_queue.pop(&mut self.randomizer, &mut self.rng) {
+            self.spawn(piece_type);
+        }
+    }
+}
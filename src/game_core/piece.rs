@@ -1,7 +1,19 @@
-use crate::components::Piece;
-use crate::game_color::GameColor;
-use crate::game_types::{PieceMatrix, PieceType, Presence};
-use rand::{Rng, rng};
+use super::board::{PieceMatrix, PieceType, Presence};
+use super::color::GameColor;
+
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Piece {
+    pub states: [u16; 4],
+    pub color: GameColor,
+    pub current_state: usize,
+    pub piece_type: PieceType,
+}
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct Position {
+    pub x: isize,
+    pub y: isize,
+}
 
 pub fn get_block_matrix(num: u16, color: GameColor) -> PieceMatrix {
     let mut res = [[Presence::No; 4]; 4];
@@ -17,60 +29,48 @@ impl From<PieceType> for Piece {
     fn from(piece_type: PieceType) -> Piece {
         use PieceType::*;
 
-        let def = Piece::default();
+        // Rotation states follow SRS: spawn, R, 2, L.
+        let def = Piece {
+            piece_type,
+            ..Piece::default()
+        };
 
         match piece_type {
             L => Piece {
-                states: [17504, 1856, 1570, 736],
+                states: [11776, 17504, 3712, 50240],
                 color: GameColor::Orange,
                 ..def
             },
             J => Piece {
-                states: [8800, 1136, 1604, 3616],
+                states: [36352, 25664, 3616, 17600],
                 color: GameColor::Blue,
                 ..def
             },
             S => Piece {
-                states: [17952, 1728, 17952, 1728],
+                states: [27648, 17952, 1728, 35904],
                 color: GameColor::Green,
                 ..def
             },
             Z => Piece {
-                states: [9792, 3168, 9792, 3168],
+                states: [50688, 9792, 3168, 19584],
                 color: GameColor::Red,
                 ..def
             },
             T => Piece {
-                states: [17984, 3648, 19520, 19968],
+                states: [19968, 17984, 3648, 19520],
                 color: GameColor::Purple,
                 ..def
             },
             I => Piece {
-                states: [17476, 3840, 17476, 3840],
+                states: [3840, 8738, 240, 17476],
                 color: GameColor::Cyan,
                 ..def
             },
             O => Piece {
-                states: [1632, 1632, 1632, 1632],
+                states: [26112, 26112, 26112, 26112],
                 color: GameColor::Yellow,
                 ..def
             },
         }
     }
 }
-
-impl Piece {
-    pub fn random() -> Self {
-        let mut rng = rng();
-        let piece_type = match rng.random_range(0..7) {
-            0 => PieceType::L,
-            1 => PieceType::J,
-            2 => PieceType::S,
-            3 => PieceType::Z,
-            4 => PieceType::T,
-            5 => PieceType::I,
-            _ => PieceType::O,
-        };
-        Piece::from(piece_type)
-    }
-}
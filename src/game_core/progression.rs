@@ -0,0 +1,87 @@
+use super::scoring::action_points;
+use super::tspin::TSpin;
+
+pub const MAX_LEVEL: u32 = 20;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum LevelGoal {
+    // ten cleared lines per level
+    #[default]
+    Fixed,
+    // five times the level in awarded lines, where harder clears award more
+    Variable,
+}
+
+impl LevelGoal {
+    pub fn toggled(self) -> Self {
+        match self {
+            LevelGoal::Fixed => LevelGoal::Variable,
+            LevelGoal::Variable => LevelGoal::Fixed,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            LevelGoal::Fixed => "fixed",
+            LevelGoal::Variable => "variable",
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct LevelProgression {
+    pub goal: LevelGoal,
+    pub max_level: u32,
+}
+
+impl Default for LevelProgression {
+    fn default() -> Self {
+        LevelProgression {
+            goal: LevelGoal::Fixed,
+            max_level: MAX_LEVEL,
+        }
+    }
+}
+
+impl LevelProgression {
+    pub fn lines_for(&self, lines_cleared: u32, tspin: TSpin) -> u32 {
+        match self.goal {
+            LevelGoal::Fixed => lines_cleared,
+            LevelGoal::Variable => action_points(lines_cleared, tspin) / 100,
+        }
+    }
+
+    pub fn goal_for(&self, level: u32) -> u32 {
+        match self.goal {
+            LevelGoal::Fixed => 10,
+            LevelGoal::Variable => 5 * level,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Level {
+    pub value: u32,
+    pub lines_cleared_in_level: u32,
+}
+
+impl Default for Level {
+    fn default() -> Self {
+        Level {
+            value: 1,
+            lines_cleared_in_level: 0,
+        }
+    }
+}
+
+impl Level {
+    pub fn add_lines(&mut self, lines: u32, progression: &LevelProgression) {
+        self.lines_cleared_in_level += lines;
+        while self.value < progression.max_level
+            && self.lines_cleared_in_level >= progression.goal_for(self.value)
+        {
+            self.lines_cleared_in_level -= progression.goal_for(self.value);
+            self.value += 1;
+        }
+    }
+}
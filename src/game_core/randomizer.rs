@@ -0,0 +1,309 @@
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, RngCore, SeedableRng};
+use std::collections::VecDeque;
+
+use super::board::PieceType;
+use crate::game_constants::PREVIEW_ROWS;
+
+pub trait Randomizer {
+    // `None` once a finite sequence has dealt its last piece.
+    fn next_piece(&mut self, rng: &mut dyn RngCore) -> Option<PieceType>;
+
+    // Forget anything carried over from the last game, so the same seed
+    // deals the same pieces again.
+    fn reset(&mut self) {}
+}
+
+pub struct PieceRandomizer(pub Box<dyn Randomizer + Send + Sync>);
+
+impl Default for PieceRandomizer {
+    fn default() -> Self {
+        PieceRandomizer(Box::new(BagRandomizer::seven_bag()))
+    }
+}
+
+impl PieceRandomizer {
+    pub fn next_piece(&mut self, rng: &mut dyn RngCore) -> Option<PieceType> {
+        self.0.next_piece(rng)
+    }
+
+    pub fn reset(&mut self) {
+        self.0.reset();
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BagRandomizer {
+    copies: usize,
+    bag: Vec<PieceType>,
+}
+
+impl BagRandomizer {
+    pub fn new(copies: usize) -> Self {
+        BagRandomizer {
+            copies: copies.max(1),
+            bag: Vec::new(),
+        }
+    }
+
+    pub fn seven_bag() -> Self {
+        Self::new(1)
+    }
+
+    pub fn fourteen_bag() -> Self {
+        Self::new(2)
+    }
+}
+
+impl Randomizer for BagRandomizer {
+    fn next_piece(&mut self, rng: &mut dyn RngCore) -> Option<PieceType> {
+        if self.bag.is_empty() {
+            for _ in 0..self.copies {
+                self.bag.extend_from_slice(&PieceType::ALL);
+            }
+            self.bag.shuffle(rng);
+        }
+        self.bag.pop()
+    }
+
+    fn reset(&mut self) {
+        self.bag.clear();
+    }
+}
+
+fn random_piece(rng: &mut dyn RngCore) -> PieceType {
+    PieceType::ALL[rng.random_range(0..PieceType::ALL.len())]
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PureRandomizer;
+
+impl Randomizer for PureRandomizer {
+    fn next_piece(&mut self, rng: &mut dyn RngCore) -> Option<PieceType> {
+        Some(random_piece(rng))
+    }
+}
+
+// TGM style: reroll a few times when the piece is in the recent history,
+// and never open with an S, Z or O.
+#[derive(Debug, Clone)]
+pub struct HistoryRandomizer {
+    history: [PieceType; 4],
+    rolls: usize,
+    first: bool,
+}
+
+impl HistoryRandomizer {
+    pub fn new(rolls: usize) -> Self {
+        HistoryRandomizer {
+            history: [PieceType::Z; 4],
+            rolls: rolls.max(1),
+            first: true,
+        }
+    }
+}
+
+impl Default for HistoryRandomizer {
+    fn default() -> Self {
+        Self::new(4)
+    }
+}
+
+impl Randomizer for HistoryRandomizer {
+    fn next_piece(&mut self, rng: &mut dyn RngCore) -> Option<PieceType> {
+        let piece = if self.first {
+            self.first = false;
+            const OPENERS: [PieceType; 4] =
+                [PieceType::L, PieceType::J, PieceType::T, PieceType::I];
+            OPENERS[rng.random_range(0..OPENERS.len())]
+        } else {
+            let mut piece = random_piece(rng);
+            for _ in 1..self.rolls {
+                if !self.history.contains(&piece) {
+                    break;
+                }
+                piece = random_piece(rng);
+            }
+            piece
+        };
+        self.history.rotate_right(1);
+        self.history[0] = piece;
+        Some(piece)
+    }
+
+    fn reset(&mut self) {
+        *self = Self::new(self.rolls);
+    }
+}
+
+// Deals a fixed list of pieces in order and nothing after the last one.
+#[derive(Debug, Clone)]
+pub struct SequenceRandomizer {
+    pieces: Vec<PieceType>,
+    next: usize,
+}
+
+impl SequenceRandomizer {
+    pub fn new(pieces: Vec<PieceType>) -> Self {
+        SequenceRandomizer { pieces, next: 0 }
+    }
+}
+
+impl Randomizer for SequenceRandomizer {
+    fn next_piece(&mut self, _rng: &mut dyn RngCore) -> Option<PieceType> {
+        let piece = self.pieces.get(self.next).copied();
+        self.next += piece.is_some() as usize;
+        piece
+    }
+
+    fn reset(&mut self) {
+        self.next = 0;
+    }
+}
+
+// The randomizers a player can pick for their games.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum RandomizerKind {
+    #[default]
+    SevenBag,
+    FourteenBag,
+    Pure,
+    History,
+}
+
+impl RandomizerKind {
+    pub const ALL: [RandomizerKind; 4] = [
+        RandomizerKind::SevenBag,
+        RandomizerKind::FourteenBag,
+        RandomizerKind::Pure,
+        RandomizerKind::History,
+    ];
+
+    pub fn build(self) -> PieceRandomizer {
+        match self {
+            RandomizerKind::SevenBag => PieceRandomizer(Box::new(BagRandomizer::seven_bag())),
+            RandomizerKind::FourteenBag => PieceRandomizer(Box::new(BagRandomizer::fourteen_bag())),
+            RandomizerKind::Pure => PieceRandomizer(Box::new(PureRandomizer)),
+            RandomizerKind::History => PieceRandomizer(Box::new(HistoryRandomizer::default())),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            RandomizerKind::SevenBag => "7-bag",
+            RandomizerKind::FourteenBag => "14-bag",
+            RandomizerKind::Pure => "pure",
+            RandomizerKind::History => "history",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.name() == name)
+    }
+
+    // Moves to the neighbouring kind, stopping at either end.
+    pub fn step(&mut self, direction: isize) {
+        let index = Self::ALL.iter().position(|kind| kind == self).unwrap_or(0) as isize;
+        *self = Self::ALL[(index + direction).clamp(0, Self::ALL.len() as isize - 1) as usize];
+    }
+}
+
+pub const MAX_PREVIEWS: usize = 6;
+
+pub struct NextQueue {
+    pieces: VecDeque<PieceType>,
+    preview_count: usize,
+}
+
+impl NextQueue {
+    pub fn new(preview_count: usize) -> Self {
+        NextQueue {
+            pieces: VecDeque::new(),
+            preview_count: preview_count.clamp(1, MAX_PREVIEWS),
+        }
+    }
+
+    pub fn preview_count(&self) -> usize {
+        self.preview_count
+    }
+
+    pub fn pieces(&self) -> impl Iterator<Item = PieceType> + '_ {
+        self.pieces.iter().copied()
+    }
+
+    // As many previews as fit in a panel `rows` tall.
+    pub fn shown_pieces(&self, rows: usize) -> impl Iterator<Item = PieceType> + '_ {
+        self.pieces().take(rows / PREVIEW_ROWS)
+    }
+
+    pub fn clear(&mut self) {
+        self.pieces.clear();
+    }
+
+    // A finite randomizer leaves the queue short once it runs dry, so the
+    // previews only show the pieces that are left.
+    pub fn fill(&mut self, randomizer: &mut PieceRandomizer, rng: &mut GameRng) {
+        while self.pieces.len() < self.preview_count {
+            let Some(piece_type) = randomizer.next_piece(rng) else {
+                break;
+            };
+            self.pieces.push_back(piece_type);
+        }
+    }
+
+    pub fn pop(
+        &mut self,
+        randomizer: &mut PieceRandomizer,
+        rng: &mut GameRng,
+    ) -> Option<PieceType> {
+        self.fill(randomizer, rng);
+        let piece_type = self.pieces.pop_front();
+        self.fill(randomizer, rng);
+        piece_type
+    }
+}
+
+impl Default for NextQueue {
+    fn default() -> Self {
+        Self::new(5)
+    }
+}
+
+pub struct GameRng {
+    seed: u64,
+    rng: StdRng,
+}
+
+impl GameRng {
+    pub fn from_seed(seed: u64) -> Self {
+        GameRng {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+impl Default for GameRng {
+    fn default() -> Self {
+        Self::from_seed(rand::rng().random())
+    }
+}
+
+impl RngCore for GameRng {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        self.rng.fill_bytes(dst)
+    }
+}
@@ -0,0 +1,77 @@
+use super::progression::MAX_LEVEL;
+use super::tspin::TSpin;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Score {
+    pub value: u32,
+    pub combo: Option<u32>,
+    pub back_to_back: bool,
+}
+
+pub fn action_points(lines: u32, tspin: TSpin) -> u32 {
+    match (tspin, lines) {
+        (TSpin::None, 0) => 0,
+        (TSpin::None, 1) => 100,
+        (TSpin::None, 2) => 300,
+        (TSpin::None, 3) => 500,
+        (TSpin::None, _) => 800,
+        (TSpin::Mini, 0) => 100,
+        (TSpin::Mini, 1) => 200,
+        (TSpin::Mini, _) => 400,
+        (TSpin::Full, 0) => 400,
+        (TSpin::Full, 1) => 800,
+        (TSpin::Full, 2) => 1200,
+        (TSpin::Full, _) => 1600,
+    }
+}
+
+pub fn perfect_clear_points(lines: u32, back_to_back: bool) -> u32 {
+    match lines {
+        0 => 0,
+        1 => 800,
+        2 => 1200,
+        3 => 1800,
+        _ if back_to_back => 3200,
+        _ => 2000,
+    }
+}
+
+// Tetrises and T-spins that clear lines keep a back-to-back chain going.
+pub fn is_difficult(lines: u32, tspin: TSpin) -> bool {
+    lines >= 4 || (lines > 0 && tspin != TSpin::None)
+}
+
+impl Score {
+    pub fn award_lock(&mut self, lines: u32, tspin: TSpin, perfect_clear: bool, level: u32) -> u32 {
+        // Master counts levels 0 to 999; the multiplier stays on the
+        // Guideline's 1 to 20 scale.
+        let level = level.clamp(1, MAX_LEVEL);
+        let difficult = is_difficult(lines, tspin);
+        let back_to_back = difficult && self.back_to_back;
+
+        let mut points = action_points(lines, tspin) * level;
+        if back_to_back {
+            points = points * 3 / 2;
+        }
+
+        if lines > 0 {
+            let combo = self.combo.map_or(0, |combo| combo + 1);
+            points += 50 * combo * level;
+            self.combo = Some(combo);
+            self.back_to_back = difficult;
+        } else {
+            self.combo = None;
+        }
+
+        if perfect_clear {
+            points += perfect_clear_points(lines, back_to_back) * level;
+        }
+
+        self.value += points;
+        points
+    }
+
+    pub fn award_drop(&mut self, cells: u32, hard: bool) {
+        self.value += if hard { cells * 2 } else { cells };
+    }
+}
@@ -0,0 +1,95 @@
+use super::board::{GameMap, PieceType};
+use super::piece::{Piece, Position};
+
+pub type Kick = (isize, isize);
+
+// Kick tables from the Guideline, written with y pointing up as in the spec.
+// Each row is indexed by the state the piece rotates from.
+const JLSTZ_CW_KICKS: [[Kick; 5]; 4] = [
+    [(0, 0), (-1, 0), (-1, 1), (0, -2), (-1, -2)],
+    [(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)],
+    [(0, 0), (1, 0), (1, 1), (0, -2), (1, -2)],
+    [(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)],
+];
+
+const JLSTZ_CCW_KICKS: [[Kick; 5]; 4] = [
+    [(0, 0), (1, 0), (1, 1), (0, -2), (1, -2)],
+    [(0, 0), (1, 0), (1, -1), (0, 2), (1, 2)],
+    [(0, 0), (-1, 0), (-1, 1), (0, -2), (-1, -2)],
+    [(0, 0), (-1, 0), (-1, -1), (0, 2), (-1, 2)],
+];
+
+const I_CW_KICKS: [[Kick; 5]; 4] = [
+    [(0, 0), (-2, 0), (1, 0), (-2, -1), (1, 2)],
+    [(0, 0), (-1, 0), (2, 0), (-1, 2), (2, -1)],
+    [(0, 0), (2, 0), (-1, 0), (2, 1), (-1, -2)],
+    [(0, 0), (1, 0), (-2, 0), (1, -2), (-2, 1)],
+];
+
+const I_CCW_KICKS: [[Kick; 5]; 4] = [
+    [(0, 0), (-1, 0), (2, 0), (-1, 2), (2, -1)],
+    [(0, 0), (2, 0), (-1, 0), (2, 1), (-1, -2)],
+    [(0, 0), (1, 0), (-2, 0), (1, -2), (-2, 1)],
+    [(0, 0), (-2, 0), (1, 0), (-2, -1), (1, 2)],
+];
+
+const O_KICKS: [Kick; 1] = [(0, 0)];
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Rotation {
+    Clockwise,
+    CounterClockwise,
+}
+
+impl Rotation {
+    pub fn apply(self, state: usize) -> usize {
+        match self {
+            Rotation::Clockwise => (state + 1) % 4,
+            Rotation::CounterClockwise => (state + 3) % 4,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct RotationResult {
+    pub state: usize,
+    pub position: Position,
+    pub kick_index: usize,
+}
+
+pub fn kick_offsets(piece_type: PieceType, from: usize, rotation: Rotation) -> &'static [Kick] {
+    match (piece_type, rotation) {
+        (PieceType::O, _) => &O_KICKS,
+        (PieceType::I, Rotation::Clockwise) => &I_CW_KICKS[from],
+        (PieceType::I, Rotation::CounterClockwise) => &I_CCW_KICKS[from],
+        (_, Rotation::Clockwise) => &JLSTZ_CW_KICKS[from],
+        (_, Rotation::CounterClockwise) => &JLSTZ_CCW_KICKS[from],
+    }
+}
+
+pub fn try_rotate(
+    piece: &Piece,
+    position: &Position,
+    rotation: Rotation,
+    game_map: &GameMap,
+) -> Option<RotationResult> {
+    let state = rotation.apply(piece.current_state);
+
+    for (kick_index, &(dx, dy)) in kick_offsets(piece.piece_type, piece.current_state, rotation)
+        .iter()
+        .enumerate()
+    {
+        let kicked = Position {
+            x: position.x + dx,
+            y: position.y - dy,
+        };
+        if !game_map.collides(piece, state, kicked.x, kicked.y) {
+            return Some(RotationResult {
+                state,
+                position: kicked,
+                kick_index,
+            });
+        }
+    }
+    None
+}
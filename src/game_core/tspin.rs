@@ -0,0 +1,88 @@
+use super::board::{GameMap, PieceType};
+use super::piece::{Piece, Position};
+
+// Corners of the T's 3x3 box, and the two that sit in front of the flat
+// side for each rotation state.
+const CORNERS: [(isize, isize); 4] = [(0, 0), (2, 0), (0, 2), (2, 2)];
+const FRONT_CORNERS: [[(isize, isize); 2]; 4] = [
+    [(0, 0), (2, 0)],
+    [(2, 0), (2, 2)],
+    [(0, 2), (2, 2)],
+    [(0, 0), (0, 2)],
+];
+
+// The fifth SRS test: a T-spin mini that needed it counts as a full T-spin.
+const TST_KICK_INDEX: usize = 4;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum TSpin {
+    #[default]
+    None,
+    Mini,
+    Full,
+}
+
+impl TSpin {
+    pub fn label(self, lines: u32) -> Option<String> {
+        let base = match self {
+            TSpin::None => return None,
+            TSpin::Mini => "Mini T-Spin",
+            TSpin::Full => "T-Spin",
+        };
+        Some(match lines {
+            0 => base.to_string(),
+            1 => format!("{base} Single"),
+            2 => format!("{base} Double"),
+            _ => format!("{base} Triple"),
+        })
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct PieceLocked {
+    pub piece_type: PieceType,
+    // where the piece came to rest, for finesse checks
+    pub position: Position,
+    pub rotation: usize,
+    pub tspin: TSpin,
+    pub lines_cleared: u32,
+    pub perfect_clear: bool,
+}
+
+fn is_occupied(game_map: &GameMap, x: isize, y: isize) -> bool {
+    if x < 0 || x >= game_map.width() as isize || y >= game_map.height() as isize {
+        return true;
+    }
+    y >= 0 && game_map.is_occupied(x as usize, y as usize)
+}
+
+pub fn detect_tspin(
+    piece: &Piece,
+    position: &Position,
+    game_map: &GameMap,
+    rotation_kick: Option<usize>,
+) -> TSpin {
+    let Some(kick_index) = rotation_kick else {
+        return TSpin::None;
+    };
+    if piece.piece_type != PieceType::T {
+        return TSpin::None;
+    }
+
+    let occupied =
+        |(dx, dy): (isize, isize)| is_occupied(game_map, position.x + dx, position.y + dy);
+    if CORNERS
+        .into_iter()
+        .filter(|&corner| occupied(corner))
+        .count()
+        < 3
+    {
+        return TSpin::None;
+    }
+    let front = FRONT_CORNERS[piece.current_state];
+    if front.into_iter().all(occupied) || kick_index == TST_KICK_INDEX {
+        TSpin::Full
+    } else {
+        TSpin::Mini
+    }
+}
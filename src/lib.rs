@@ -1,8 +1,12 @@
-pub mod components;
-pub mod game_color;
+pub mod auto_repeat;
+pub mod finesse;
 pub mod game_constants;
-pub mod game_types;
-pub mod piece_utils;
+pub mod game_core;
+pub mod menu;
+pub mod modes;
+pub mod records;
 pub mod resources;
+pub mod stack_display;
 pub mod state;
+pub mod stats;
 pub mod systems;
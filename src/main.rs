@@ -1,54 +1,199 @@
 use bevy::prelude::*;
 
-mod components;
-mod game_color;
-mod game_constants;
-mod game_types;
-mod piece_utils;
-mod resources;
-mod state;
-mod systems;
-
-use game_color::GameColor;
-use game_constants::{HEIGHT, TITLE, WIDTH};
-use game_types::GameMap;
-use state::GameState;
-
-use systems::input::handle_input;
-use systems::lines::clear_lines;
-use systems::movement::move_piece_down;
-use systems::rendering::draw_blocks;
-use systems::setup::setup_camera;
-use systems::spawning::spawn_initial_piece;
-use systems::time::update_gravity_speed;
+use tetris_rust_bevy_ver0_16::auto_repeat::{AutoRepeat, InputSettings};
+use tetris_rust_bevy_ver0_16::finesse::{PieceInputs, judge_finesse};
+use tetris_rust_bevy_ver0_16::game_constants::{MAX_WINDOW_HEIGHT, TITLE};
+use tetris_rust_bevy_ver0_16::game_core::color::GameColor;
+use tetris_rust_bevy_ver0_16::game_core::randomizer::RandomizerKind;
+use tetris_rust_bevy_ver0_16::menu::MenuPlugin;
+use tetris_rust_bevy_ver0_16::modes::EndlessModePlugin;
+use tetris_rust_bevy_ver0_16::modes::dig::DigModePlugin;
+use tetris_rust_bevy_ver0_16::modes::marathon::MarathonModePlugin;
+use tetris_rust_bevy_ver0_16::modes::master::MasterModePlugin;
+use tetris_rust_bevy_ver0_16::modes::puzzle::{PuzzleDir, PuzzleModePlugin};
+use tetris_rust_bevy_ver0_16::modes::sprint::SprintModePlugin;
+use tetris_rust_bevy_ver0_16::modes::ultra::UltraModePlugin;
+use tetris_rust_bevy_ver0_16::records::{
+    BestTimes, HighScores, RecordsFile, load_records, record_finished_game, save_records,
+};
+use tetris_rust_bevy_ver0_16::resources::{
+    BoardConfig, CurrentGame, LockEvent, NextSeed, RandomizerChoice,
+};
+use tetris_rust_bevy_ver0_16::stack_display::{StackReveal, reveal_stack};
+use tetris_rust_bevy_ver0_16::state::{GameResults, GameState};
+use tetris_rust_bevy_ver0_16::stats::{GameClock, GameStats, count_keys, tick_clock};
+
+use tetris_rust_bevy_ver0_16::systems::game_flow::{
+    end_topped_out_game, game_over_input, reset_game, spawn_game_over_screen, spawn_pause_screen,
+    spawn_results_screen, start_game, toggle_pause,
+};
+use tetris_rust_bevy_ver0_16::systems::hold::hold_piece;
+use tetris_rust_bevy_ver0_16::systems::hud::{
+    clear_banner, hide_hud, show_hud, spawn_hud, update_clock_hud, update_score_hud,
+};
+use tetris_rust_bevy_ver0_16::systems::input::{handle_input, handle_movement};
+use tetris_rust_bevy_ver0_16::systems::movement::step_game;
+use tetris_rust_bevy_ver0_16::systems::rendering::{
+    clear_board, draw_blocks, draw_hold, draw_next,
+};
+use tetris_rust_bevy_ver0_16::systems::setup::setup_camera;
+
+// Accepts both `--name value` and `--name=value`.
+fn arg_value(name: &str) -> Option<String> {
+    let flag = format!("--{name}");
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if let Some(value) = arg
+            .strip_prefix(&flag)
+            .and_then(|rest| rest.strip_prefix('='))
+        {
+            return Some(value.to_string());
+        }
+        if arg == flag {
+            return args.next();
+        }
+    }
+    None
+}
+
+fn seed_from_args() -> Option<u64> {
+    arg_value("seed")?.parse().ok()
+}
+
+// `--board 4x20` sets the visible field to 4 columns by 20 rows.
+fn board_from_args() -> BoardConfig {
+    arg_value("board")
+        .and_then(|value| {
+            let (width, height) = value.split_once('x')?;
+            Some(BoardConfig::new(width.parse().ok()?, height.parse().ok()?))
+        })
+        .unwrap_or_default()
+}
+
+// `--randomizer 14-bag`; one of 7-bag, 14-bag, pure or history.
+fn randomizer_from_args() -> RandomizerChoice {
+    arg_value("randomizer")
+        .and_then(|name| RandomizerKind::from_name(&name))
+        .map(RandomizerChoice)
+        .unwrap_or_default()
+}
+
+fn puzzle_dir_from_args() -> PuzzleDir {
+    arg_value("puzzles")
+        .map(|dir| PuzzleDir(dir.into()))
+        .unwrap_or_default()
+}
 
 fn main() {
+    let seed = seed_from_args();
+    if let Some(seed) = seed {
+        println!("Game seed: {seed}");
+    }
+    let board = board_from_args();
+    let window_size = board.window_size();
+    let window_scale = (MAX_WINDOW_HEIGHT / window_size.y).min(1.0);
+
     App::new()
         .insert_resource(ClearColor(GameColor::Gray.into()))
         .add_plugins(DefaultPlugins.set(WindowPlugin {
             primary_window: Some(Window {
                 title: TITLE.into(),
-                resolution: (WIDTH as f32, HEIGHT as f32).into(),
+                resolution: (window_size * window_scale).into(),
                 ..default()
             }),
             ..default()
         }))
-        .init_resource::<GameMap>()
-        .init_resource::<resources::Score>()
-        .init_resource::<resources::Level>()
-        .insert_resource(Time::<Fixed>::from_seconds(2.0))
-        .insert_state(GameState::Playing)
+        .insert_resource(board)
+        .init_resource::<CurrentGame>()
+        .insert_resource(NextSeed(seed))
+        .insert_resource(randomizer_from_args())
+        .insert_resource(puzzle_dir_from_args())
+        .init_resource::<InputSettings>()
+        .init_resource::<AutoRepeat>()
+        .add_event::<LockEvent>()
+        .init_resource::<GameStats>()
+        .init_resource::<GameClock>()
+        .init_resource::<PieceInputs>()
+        .init_resource::<GameResults>()
+        .init_resource::<StackReveal>()
+        .init_resource::<HighScores>()
+        .init_resource::<BestTimes>()
+        .init_resource::<RecordsFile>()
+        .init_state::<GameState>()
+        .add_plugins((
+            MenuPlugin,
+            EndlessModePlugin,
+            MarathonModePlugin,
+            SprintModePlugin,
+            UltraModePlugin,
+            DigModePlugin,
+            MasterModePlugin,
+            PuzzleModePlugin,
+        ))
+        .add_systems(Startup, (setup_camera, spawn_hud, load_records))
         .add_systems(
-            Startup,
-            (setup_camera, spawn_initial_piece, update_gravity_speed),
+            OnEnter(GameState::MainMenu),
+            (reset_game, clear_board, hide_hud, clear_banner),
+        )
+        .add_systems(OnExit(GameState::MainMenu), show_hud)
+        .add_systems(OnExit(GameState::GameOver), (reset_game, clear_banner))
+        .add_systems(OnExit(GameState::Finished), (reset_game, clear_banner))
+        .add_systems(OnEnter(GameState::Playing), start_game)
+        .add_systems(OnEnter(GameState::Paused), spawn_pause_screen)
+        .add_systems(
+            OnEnter(GameState::GameOver),
+            (
+                record_finished_game,
+                save_records.after(record_finished_game),
+                spawn_game_over_screen,
+            ),
+        )
+        .add_systems(
+            OnEnter(GameState::Finished),
+            (
+                record_finished_game,
+                save_records.after(record_finished_game),
+                spawn_results_screen,
+            ),
         )
         .add_systems(
             Update,
-            (handle_input, draw_blocks, clear_lines, update_gravity_speed),
+            (
+                toggle_pause.run_if(in_state(GameState::Playing).or(in_state(GameState::Paused))),
+                game_over_input
+                    .run_if(in_state(GameState::GameOver).or(in_state(GameState::Finished))),
+            ),
         )
         .add_systems(
-            FixedUpdate,
-            move_piece_down.run_if(in_state(GameState::Playing)),
+            Update,
+            (
+                hold_piece.before(handle_input),
+                handle_input,
+                handle_movement.after(handle_input),
+                step_game.after(handle_movement),
+                reveal_stack.after(step_game),
+                count_keys,
+                judge_finesse.after(step_game),
+                tick_clock,
+                end_topped_out_game.after(step_game),
+            )
+                .run_if(in_state(GameState::Playing)),
+        )
+        .add_systems(
+            Update,
+            (
+                draw_blocks,
+                draw_hold.after(draw_blocks),
+                draw_next.after(draw_blocks),
+            )
+                .run_if(not(in_state(GameState::MainMenu))),
+        )
+        .add_systems(
+            Update,
+            (
+                update_score_hud.run_if(resource_changed::<CurrentGame>),
+                update_clock_hud.run_if(resource_changed::<GameClock>),
+            ),
         )
         .run();
 }
@@ -0,0 +1,40 @@
+use bevy::prelude::*;
+
+use crate::modes::GameModes;
+use crate::records::HighScores;
+
+use super::{MenuCursor, MenuState, spawn_menu};
+
+// Each mode's leaderboard reads its own records from the world.
+pub fn spawn_high_scores(world: &World, mut commands: Commands) {
+    let modes = world.resource::<GameModes>();
+    let mut lines = Vec::new();
+    for mode in modes.iter() {
+        let leaderboard = modes.leaderboard(mode.id);
+        lines.push(format!("{} ({})", mode.name, leaderboard.ranking.label()));
+        lines.extend((leaderboard.render)(world, mode.id));
+    }
+    spawn_menu(
+        &mut commands,
+        MenuState::HighScores,
+        "High Scores",
+        &lines,
+        "Esc: back",
+    );
+}
+
+// nothing to select on this screen
+pub fn clear_high_scores_cursor(mut cursor: ResMut<MenuCursor>) {
+    cursor.reset(0);
+}
+
+pub struct HighScoresMenuPlugin;
+
+impl Plugin for HighScoresMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HighScores>().add_systems(
+            OnEnter(MenuState::HighScores),
+            (spawn_high_scores, clear_high_scores_cursor),
+        );
+    }
+}
@@ -0,0 +1,50 @@
+use bevy::app::AppExit;
+use bevy::input::ButtonInput;
+use bevy::input::keyboard::KeyCode;
+use bevy::prelude::*;
+
+use super::{MENU_CONFIRM_KEYS, MenuCursor, MenuState, spawn_menu};
+
+const ENTRIES: [&str; 5] = ["Play", "Settings", "High Scores", "Replays", "Quit"];
+
+pub fn spawn_main_menu(mut commands: Commands, mut cursor: ResMut<MenuCursor>) {
+    let entries: Vec<String> = ENTRIES.iter().map(|entry| entry.to_string()).collect();
+    spawn_menu(
+        &mut commands,
+        MenuState::Main,
+        "TETRIS",
+        &entries,
+        "Up/Down: choose   Enter: select",
+    );
+    cursor.reset(entries.len());
+}
+
+pub fn activate_main_menu(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    cursor: Res<MenuCursor>,
+    mut next_state: ResMut<NextState<MenuState>>,
+    mut exit: EventWriter<AppExit>,
+) {
+    if !keyboard_input.any_just_pressed(MENU_CONFIRM_KEYS) {
+        return;
+    }
+    match cursor.index {
+        0 => next_state.set(MenuState::ModeSelect),
+        1 => next_state.set(MenuState::Settings),
+        2 => next_state.set(MenuState::HighScores),
+        3 => next_state.set(MenuState::Replays),
+        _ => {
+            exit.write(AppExit::Success);
+        }
+    }
+}
+
+pub struct MainMenuPlugin;
+
+impl Plugin for MainMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<AppExit>()
+            .add_systems(OnEnter(MenuState::Main), spawn_main_menu)
+            .add_systems(Update, activate_main_menu.run_if(in_state(MenuState::Main)));
+    }
+}
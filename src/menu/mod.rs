@@ -0,0 +1,173 @@
+use bevy::input::ButtonInput;
+use bevy::input::keyboard::KeyCode;
+use bevy::prelude::*;
+
+use crate::state::GameState;
+
+pub mod high_scores;
+pub mod main_menu;
+pub mod mode_select;
+pub mod replays;
+pub mod settings;
+
+pub const MENU_UP_KEYS: [KeyCode; 2] = [KeyCode::ArrowUp, KeyCode::KeyW];
+pub const MENU_DOWN_KEYS: [KeyCode; 2] = [KeyCode::ArrowDown, KeyCode::KeyS];
+pub const MENU_CONFIRM_KEYS: [KeyCode; 2] = [KeyCode::Enter, KeyCode::Space];
+pub const MENU_BACK_KEYS: [KeyCode; 2] = [KeyCode::Escape, KeyCode::Backspace];
+
+const SELECTED_COLOR: Color = Color::srgb(1.0, 0.84, 0.0);
+const ENTRY_COLOR: Color = Color::WHITE;
+
+// Each menu screen is a sub-state of `GameState::MainMenu` with its own
+// plugin, so entities spawned for a screen go away when it is left.
+#[derive(SubStates, Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+#[source(GameState = GameState::MainMenu)]
+#[states(scoped_entities)]
+pub enum MenuState {
+    #[default]
+    Main,
+    ModeSelect,
+    Settings,
+    HighScores,
+    Replays,
+}
+
+#[derive(Resource, Debug, Default)]
+pub struct MenuCursor {
+    pub index: usize,
+    pub len: usize,
+}
+
+impl MenuCursor {
+    pub fn reset(&mut self, len: usize) {
+        self.index = 0;
+        self.len = len;
+    }
+
+    // Wraps around at either end.
+    pub fn step(&mut self, delta: isize) {
+        if self.len > 0 {
+            self.index = (self.index as isize + delta).rem_euclid(self.len as isize) as usize;
+        }
+    }
+}
+
+#[derive(Component, Debug)]
+pub struct MenuEntry(pub usize);
+
+// Spawns a screen with a title, one selectable line per entry and an
+// optional hint at the bottom.
+pub fn spawn_menu(
+    commands: &mut Commands,
+    state: MenuState,
+    title: &str,
+    entries: &[String],
+    footer: &str,
+) {
+    commands
+        .spawn((
+            StateScoped(state),
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(10.0),
+                ..default()
+            },
+            BackgroundColor(Color::BLACK.with_alpha(0.8)),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(title),
+                TextFont {
+                    font_size: 48.0,
+                    ..default()
+                },
+                Node {
+                    margin: UiRect::bottom(Val::Px(20.0)),
+                    ..default()
+                },
+            ));
+            for (i, entry) in entries.iter().enumerate() {
+                parent.spawn((
+                    MenuEntry(i),
+                    Text::new(entry.clone()),
+                    TextFont {
+                        font_size: 26.0,
+                        ..default()
+                    },
+                    TextColor(ENTRY_COLOR),
+                ));
+            }
+            parent.spawn((
+                Text::new(footer),
+                TextFont {
+                    font_size: 16.0,
+                    ..default()
+                },
+                Node {
+                    margin: UiRect::top(Val::Px(20.0)),
+                    ..default()
+                },
+            ));
+        });
+}
+
+pub fn navigate_menu(keyboard_input: Res<ButtonInput<KeyCode>>, mut cursor: ResMut<MenuCursor>) {
+    if keyboard_input.any_just_pressed(MENU_UP_KEYS) {
+        cursor.step(-1);
+    }
+    if keyboard_input.any_just_pressed(MENU_DOWN_KEYS) {
+        cursor.step(1);
+    }
+}
+
+pub fn highlight_entries(
+    cursor: Res<MenuCursor>,
+    mut entries: Query<(&MenuEntry, &mut TextColor)>,
+) {
+    for (entry, mut color) in entries.iter_mut() {
+        color.0 = if entry.0 == cursor.index {
+            SELECTED_COLOR
+        } else {
+            ENTRY_COLOR
+        };
+    }
+}
+
+pub fn back_to_main_menu(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    state: Res<State<MenuState>>,
+    mut next_state: ResMut<NextState<MenuState>>,
+) {
+    if *state.get() != MenuState::Main && keyboard_input.any_just_pressed(MENU_BACK_KEYS) {
+        next_state.set(MenuState::Main);
+    }
+}
+
+pub struct MenuPlugin;
+
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_sub_state::<MenuState>()
+            .init_resource::<MenuCursor>()
+            .add_systems(
+                Update,
+                (
+                    navigate_menu,
+                    highlight_entries.after(navigate_menu),
+                    back_to_main_menu,
+                )
+                    .run_if(in_state(GameState::MainMenu)),
+            )
+            .add_plugins((
+                main_menu::MainMenuPlugin,
+                mode_select::ModeSelectPlugin,
+                settings::SettingsMenuPlugin,
+                high_scores::HighScoresMenuPlugin,
+                replays::ReplaysMenuPlugin,
+            ));
+    }
+}
@@ -0,0 +1,57 @@
+use bevy::input::ButtonInput;
+use bevy::input::keyboard::KeyCode;
+use bevy::prelude::*;
+
+use crate::modes::{GameModes, SelectedMode};
+use crate::state::GameState;
+
+use super::{MENU_CONFIRM_KEYS, MenuCursor, MenuState, spawn_menu};
+
+pub fn spawn_mode_select(
+    mut commands: Commands,
+    modes: Res<GameModes>,
+    mut cursor: ResMut<MenuCursor>,
+) {
+    let entries: Vec<String> = modes
+        .iter()
+        .map(|mode| format!("{} - {}", mode.name, mode.description))
+        .collect();
+    spawn_menu(
+        &mut commands,
+        MenuState::ModeSelect,
+        "Select Mode",
+        &entries,
+        "Enter: start   Esc: back",
+    );
+    cursor.reset(entries.len());
+}
+
+pub fn start_selected_mode(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    cursor: Res<MenuCursor>,
+    modes: Res<GameModes>,
+    mut selected: ResMut<SelectedMode>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if !keyboard_input.any_just_pressed(MENU_CONFIRM_KEYS) {
+        return;
+    }
+    if let Some(mode) = modes.iter().nth(cursor.index) {
+        selected.0 = mode.id;
+        next_state.set(GameState::Playing);
+    }
+}
+
+pub struct ModeSelectPlugin;
+
+impl Plugin for ModeSelectPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GameModes>()
+            .init_resource::<SelectedMode>()
+            .add_systems(OnEnter(MenuState::ModeSelect), spawn_mode_select)
+            .add_systems(
+                Update,
+                start_selected_mode.run_if(in_state(MenuState::ModeSelect)),
+            );
+    }
+}
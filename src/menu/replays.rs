@@ -0,0 +1,68 @@
+use bevy::input::ButtonInput;
+use bevy::input::keyboard::KeyCode;
+use bevy::prelude::*;
+
+use crate::game_core::randomizer::GameRng;
+use crate::modes::{GameModes, SelectedMode};
+use crate::records::ReplayLog;
+use crate::resources::CurrentGame;
+use crate::state::GameState;
+
+use super::{MENU_CONFIRM_KEYS, MenuCursor, MenuState, spawn_menu};
+
+pub fn spawn_replays(
+    mut commands: Commands,
+    modes: Res<GameModes>,
+    replay_log: Res<ReplayLog>,
+    mut cursor: ResMut<MenuCursor>,
+) {
+    let entries: Vec<String> = replay_log
+        .entries()
+        .map(|replay| {
+            let name = modes.get(replay.mode).map_or(replay.mode, |mode| mode.name);
+            format!("{name}  seed {}  score {}", replay.seed, replay.score)
+        })
+        .collect();
+    let footer = if entries.is_empty() {
+        "No games played yet   Esc: back"
+    } else {
+        "Enter: play this seed again   Esc: back"
+    };
+    spawn_menu(
+        &mut commands,
+        MenuState::Replays,
+        "Replays",
+        &entries,
+        footer,
+    );
+    cursor.reset(entries.len());
+}
+
+pub fn start_replay(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    cursor: Res<MenuCursor>,
+    replay_log: Res<ReplayLog>,
+    mut selected: ResMut<SelectedMode>,
+    mut game: ResMut<CurrentGame>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if !keyboard_input.any_just_pressed(MENU_CONFIRM_KEYS) {
+        return;
+    }
+    if let Some(replay) = replay_log.get(cursor.index) {
+        selected.0 = replay.mode;
+        game.rng = GameRng::from_seed(replay.seed);
+        game.randomizer.reset();
+        next_state.set(GameState::Playing);
+    }
+}
+
+pub struct ReplaysMenuPlugin;
+
+impl Plugin for ReplaysMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ReplayLog>()
+            .add_systems(OnEnter(MenuState::Replays), spawn_replays)
+            .add_systems(Update, start_replay.run_if(in_state(MenuState::Replays)));
+    }
+}
@@ -0,0 +1,156 @@
+use bevy::input::ButtonInput;
+use bevy::input::keyboard::KeyCode;
+use bevy::prelude::*;
+
+use crate::auto_repeat::InputSettings;
+use crate::game_core::randomizer::{MAX_PREVIEWS, NextQueue};
+use crate::modes::{GameModes, RegisterGameMode, Setting};
+use crate::resources::{CurrentGame, RandomizerChoice};
+use crate::stack_display::StackDisplay;
+
+use super::{MenuCursor, MenuEntry, MenuState, spawn_menu};
+
+// Settings that apply to every mode; modes register their own after these.
+const GENERAL_SETTINGS: [Setting; 8] = [
+    Setting {
+        label: |world| format!("DAS: {} ms", world.resource::<InputSettings>().das_ms),
+        adjust: |world, direction| {
+            let mut input = world.resource_mut::<InputSettings>();
+            input.das_ms = (input.das_ms + 10.0 * direction as f32).clamp(0.0, 500.0);
+        },
+    },
+    Setting {
+        label: |world| format!("ARR: {} ms", world.resource::<InputSettings>().arr_ms),
+        adjust: |world, direction| {
+            let mut input = world.resource_mut::<InputSettings>();
+            input.arr_ms = (input.arr_ms + 5.0 * direction as f32).clamp(0.0, 200.0);
+        },
+    },
+    Setting {
+        label: |world| {
+            let factor = world.resource::<InputSettings>().soft_drop_factor;
+            format!("Soft drop: {factor}x")
+        },
+        adjust: |world, direction| {
+            let mut input = world.resource_mut::<InputSettings>();
+            input.soft_drop_factor =
+                (input.soft_drop_factor + 5.0 * direction as f32).clamp(1.0, 40.0);
+        },
+    },
+    Setting {
+        label: |world| {
+            let cut = world.resource::<InputSettings>().das_cut_ms;
+            format!("DAS cut: {cut} ms")
+        },
+        adjust: |world, direction| {
+            let mut input = world.resource_mut::<InputSettings>();
+            input.das_cut_ms = (input.das_cut_ms + 10.0 * direction as f32).clamp(0.0, 200.0);
+        },
+    },
+    Setting {
+        label: |world| {
+            let charge = world.resource::<InputSettings>().das_charge;
+            format!("DAS charge: {}", charge.label())
+        },
+        adjust: |world, _| {
+            let mut input = world.resource_mut::<InputSettings>();
+            input.das_charge = input.das_charge.toggled();
+        },
+    },
+    Setting {
+        label: |world| {
+            let count = world.resource::<CurrentGame>().next_queue.preview_count();
+            format!("Next previews: {count}")
+        },
+        adjust: |world, direction| {
+            let mut game = world.resource_mut::<CurrentGame>();
+            let count = (game.next_queue.preview_count() as isize + direction)
+                .clamp(1, MAX_PREVIEWS as isize);
+            game.next_queue = NextQueue::new(count as usize);
+        },
+    },
+    Setting {
+        label: |world| {
+            let choice = world.resource::<RandomizerChoice>();
+            format!("Randomizer: {}", choice.name())
+        },
+        adjust: |world, direction| {
+            let mut choice = world.resource_mut::<RandomizerChoice>();
+            choice.step(direction);
+            // the game waiting in the menu deals from the new choice too
+            let randomizer = choice.build();
+            world.resource_mut::<CurrentGame>().randomizer = randomizer;
+        },
+    },
+    Setting {
+        label: |world| format!("Stack: {}", world.resource::<StackDisplay>().label()),
+        adjust: |world, direction| world.resource_mut::<StackDisplay>().step(direction),
+    },
+];
+
+fn setting_lines(world: &World) -> Vec<String> {
+    world
+        .resource::<GameModes>()
+        .settings()
+        .iter()
+        .map(|setting| (setting.label)(world))
+        .collect()
+}
+
+pub fn spawn_settings_menu(world: &World, mut commands: Commands) {
+    spawn_menu(
+        &mut commands,
+        MenuState::Settings,
+        "Settings",
+        &setting_lines(world),
+        "Left/Right: change   Esc: back",
+    );
+}
+
+pub fn reset_settings_cursor(modes: Res<GameModes>, mut cursor: ResMut<MenuCursor>) {
+    cursor.reset(modes.settings().len());
+}
+
+pub fn change_settings(world: &mut World) {
+    let keyboard_input = world.resource::<ButtonInput<KeyCode>>();
+    let direction = if keyboard_input.just_pressed(KeyCode::ArrowLeft) {
+        -1
+    } else if keyboard_input.just_pressed(KeyCode::ArrowRight) {
+        1
+    } else {
+        return;
+    };
+    let index = world.resource::<MenuCursor>().index;
+    let Some(setting) = world.resource::<GameModes>().settings().get(index).copied() else {
+        return;
+    };
+    (setting.adjust)(world, direction);
+
+    let lines = setting_lines(world);
+    let mut entries = world.query::<(&MenuEntry, &mut Text)>();
+    for (entry, mut text) in entries.iter_mut(world) {
+        if let Some(line) = lines.get(entry.0) {
+            text.0 = line.clone();
+        }
+    }
+}
+
+pub struct SettingsMenuPlugin;
+
+impl Plugin for SettingsMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<StackDisplay>()
+            .init_resource::<RandomizerChoice>();
+        for setting in GENERAL_SETTINGS {
+            app.register_setting(setting);
+        }
+        app.add_systems(
+            OnEnter(MenuState::Settings),
+            (spawn_settings_menu, reset_settings_cursor),
+        )
+        .add_systems(
+            Update,
+            change_settings.run_if(in_state(MenuState::Settings)),
+        );
+    }
+}
@@ -0,0 +1,191 @@
+use bevy::prelude::*;
+use rand::{Rng, RngCore};
+
+use crate::game_core::Game;
+use crate::game_core::board::GameMap;
+use crate::game_core::color::GameColor;
+use crate::resources::CurrentGame;
+use crate::state::{GameResults, GameState};
+use crate::stats::{GameClock, format_time};
+use crate::systems::game_flow::{end_topped_out_game, start_game};
+use crate::systems::movement::step_game;
+
+use super::{GameModeInfo, RegisterGameMode, SelectedMode, Setting, fresh_game, mode_is};
+
+pub const DIG: GameModeInfo = GameModeInfo {
+    id: "dig",
+    name: "Dig",
+    description: "Clear your way down through the garbage",
+};
+
+pub const DIG_GOALS: [u32; 3] = [10, 18, 100];
+// Most garbage rows on the board at once, at most half the field.
+pub const GARBAGE_HEIGHT: usize = 10;
+pub const GARBAGE_COLOR: GameColor = GameColor::DarkGray;
+
+#[derive(Resource, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct DigSettings {
+    pub lines: u32,
+    // chance in percent that a row's hole moves away from the one below
+    pub messiness: u32,
+}
+
+impl Default for DigSettings {
+    fn default() -> Self {
+        DigSettings {
+            lines: 18,
+            messiness: 100,
+        }
+    }
+}
+
+impl DigSettings {
+    pub fn step_lines(&mut self, direction: isize) {
+        let index = DIG_GOALS
+            .iter()
+            .position(|&lines| lines == self.lines)
+            .unwrap_or(1) as isize;
+        self.lines = DIG_GOALS[(index + direction).clamp(0, DIG_GOALS.len() as isize - 1) as usize];
+    }
+
+    pub fn step_messiness(&mut self, direction: isize) {
+        self.messiness = (self.messiness as isize + 10 * direction).clamp(0, 100) as u32;
+    }
+}
+
+const LINES_SETTING: Setting = Setting {
+    label: |world| format!("Dig lines: {}", world.resource::<DigSettings>().lines),
+    adjust: |world, direction| world.resource_mut::<DigSettings>().step_lines(direction),
+};
+
+const MESSINESS_SETTING: Setting = Setting {
+    label: |world| {
+        format!(
+            "Dig messiness: {}%",
+            world.resource::<DigSettings>().messiness
+        )
+    },
+    adjust: |world, direction| {
+        world
+            .resource_mut::<DigSettings>()
+            .step_messiness(direction)
+    },
+};
+
+// Garbage rows still to come. The game itself keeps count of the ones on
+// the board and the ones dug out.
+#[derive(Resource, Debug, Default, Clone, PartialEq, Eq)]
+pub struct DigState {
+    pub queued: u32,
+    last_hole: Option<usize>,
+}
+
+impl DigState {
+    pub fn new(settings: &DigSettings) -> Self {
+        DigState {
+            queued: settings.lines,
+            ..default()
+        }
+    }
+
+    fn next_hole(&mut self, rng: &mut impl RngCore, width: usize, messiness: u32) -> usize {
+        let hole = match self.last_hole {
+            Some(last) if rng.random_range(0..100) >= messiness => last,
+            _ => rng.random_range(0..width),
+        };
+        self.last_hole = Some(hole);
+        hole
+    }
+
+    pub fn needs_garbage(&self, game: &Game) -> bool {
+        self.queued > 0 && game.garbage_rows < garbage_height(&game.game_map)
+    }
+
+    // Tops the garbage back up.
+    pub fn refill(&mut self, game: &mut Game, messiness: u32) {
+        let mut rows = Vec::new();
+        while self.queued > 0 && game.garbage_rows + rows.len() < garbage_height(&game.game_map) {
+            let hole = self.next_hole(&mut game.rng, game.game_map.width(), messiness);
+            // rows go in top first, so each one lands under the row its hole
+            // follows, starting with the old bottom row
+            rows.push(game.game_map.row_with_hole(hole));
+            self.queued -= 1;
+        }
+        if !rows.is_empty() {
+            game.insert_garbage(&rows, GARBAGE_COLOR);
+        }
+    }
+}
+
+pub fn garbage_height(game_map: &GameMap) -> usize {
+    GARBAGE_HEIGHT.min(game_map.visible_rows() / 2)
+}
+
+pub fn setup_dig(
+    settings: Res<DigSettings>,
+    mut dig: ResMut<DigState>,
+    mut game: ResMut<CurrentGame>,
+) {
+    *dig = DigState::new(&settings);
+    dig.refill(&mut game, settings.messiness);
+}
+
+pub fn raise_garbage(
+    settings: Res<DigSettings>,
+    mut dig: ResMut<DigState>,
+    mut game: ResMut<CurrentGame>,
+) {
+    if dig.needs_garbage(&game) {
+        dig.refill(&mut game, settings.messiness);
+    }
+}
+
+pub fn finish_dig(
+    settings: Res<DigSettings>,
+    game: Res<CurrentGame>,
+    clock: Res<GameClock>,
+    mut results: ResMut<GameResults>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if game.garbage_cleared < settings.lines {
+        return;
+    }
+    *results = GameResults {
+        headline: format!("{} Garbage Lines Dug", game.garbage_cleared),
+        lines: vec![
+            format!("Time: {}", format_time(clock.seconds())),
+            format!("Pieces: {}", game.pieces_placed),
+            format!("Lines: {}", game.lines_cleared),
+            format!("PPS: {:.2}", clock.pieces_per_second(game.pieces_placed)),
+        ],
+    };
+    next_state.set(GameState::Finished);
+}
+
+pub struct DigModePlugin;
+
+impl Plugin for DigModePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SelectedMode>()
+            .init_resource::<DigSettings>()
+            .init_resource::<DigState>()
+            .init_resource::<GameResults>()
+            .register_game_mode(DIG)
+            .register_setting(LINES_SETTING)
+            .register_setting(MESSINESS_SETTING)
+            .add_systems(
+                OnEnter(GameState::Playing),
+                setup_dig
+                    .before(start_game)
+                    .run_if(mode_is(DIG.id).and(fresh_game)),
+            )
+            .add_systems(
+                Update,
+                (
+                    raise_garbage.after(step_game).before(end_topped_out_game),
+                    finish_dig.after(step_game),
+                )
+                    .run_if(in_state(GameState::Playing).and(mode_is(DIG.id))),
+            );
+    }
+}
@@ -0,0 +1,230 @@
+use bevy::prelude::*;
+
+use crate::game_core::progression::{LevelGoal, LevelProgression};
+use crate::records::{BestTimes, HighScores, TimedRun, best_time_table, score_table};
+use crate::resources::{CurrentGame, LockEvent};
+use crate::state::{GameResults, GameState};
+use crate::stats::{GameClock, format_time};
+use crate::systems::game_flow::start_game;
+use crate::systems::hud::{HudField, set_field};
+use crate::systems::movement::step_game;
+
+use super::{
+    GameModeInfo, Leaderboard, Ranking, RegisterGameMode, SelectedMode, Setting, fresh_game,
+    mode_is,
+};
+
+pub const MARATHON: GameModeInfo = GameModeInfo {
+    id: "marathon",
+    name: "Marathon",
+    description: "Level up through the line goal",
+};
+
+pub const MARATHON_GOALS: [u32; 2] = [150, 200];
+pub const MARATHON_LEVEL_CAPS: [u32; 2] = [15, 20];
+
+#[derive(Resource, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct MarathonSettings {
+    pub line_goal: u32,
+    pub level_cap: u32,
+    pub level_goal: LevelGoal,
+    // keep playing past the line goal until the stack tops out
+    pub endless: bool,
+}
+
+impl Default for MarathonSettings {
+    fn default() -> Self {
+        MarathonSettings {
+            line_goal: 150,
+            level_cap: 15,
+            level_goal: LevelGoal::Fixed,
+            endless: false,
+        }
+    }
+}
+
+fn step_preset(presets: &[u32], value: u32, direction: isize) -> u32 {
+    let index = presets
+        .iter()
+        .position(|&preset| preset == value)
+        .unwrap_or(0) as isize;
+    presets[(index + direction).clamp(0, presets.len() as isize - 1) as usize]
+}
+
+const GOAL_SETTING: Setting = Setting {
+    label: |world| {
+        let goal = world.resource::<MarathonSettings>().line_goal;
+        format!("Marathon goal: {goal} lines")
+    },
+    adjust: |world, direction| {
+        world
+            .resource_mut::<MarathonSettings>()
+            .step_goal(direction)
+    },
+};
+
+const LEVEL_CAP_SETTING: Setting = Setting {
+    label: |world| {
+        let cap = world.resource::<MarathonSettings>().level_cap;
+        format!("Marathon level cap: {cap}")
+    },
+    adjust: |world, direction| {
+        world
+            .resource_mut::<MarathonSettings>()
+            .step_level_cap(direction)
+    },
+};
+
+const LEVEL_GOAL_SETTING: Setting = Setting {
+    label: |world| {
+        let goal = world.resource::<MarathonSettings>().level_goal;
+        format!("Marathon level goal: {}", goal.label())
+    },
+    adjust: |world, _| {
+        let mut settings = world.resource_mut::<MarathonSettings>();
+        settings.level_goal = settings.level_goal.toggled();
+    },
+};
+
+const ENDLESS_SETTING: Setting = Setting {
+    label: |world| {
+        let endless = world.resource::<MarathonSettings>().endless;
+        format!("Marathon endless: {}", if endless { "on" } else { "off" })
+    },
+    adjust: |world, _| {
+        let mut settings = world.resource_mut::<MarathonSettings>();
+        settings.endless = !settings.endless;
+    },
+};
+
+impl MarathonSettings {
+    pub fn step_goal(&mut self, direction: isize) {
+        self.line_goal = step_preset(&MARATHON_GOALS, self.line_goal, direction);
+    }
+
+    pub fn step_level_cap(&mut self, direction: isize) {
+        self.level_cap = step_preset(&MARATHON_LEVEL_CAPS, self.level_cap, direction);
+    }
+
+    // Levels up through the chosen goal, up to the cap.
+    pub fn progression(&self) -> LevelProgression {
+        LevelProgression {
+            goal: self.level_goal,
+            max_level: self.level_cap,
+        }
+    }
+}
+
+pub fn setup_marathon(settings: Res<MarathonSettings>, mut game: ResMut<CurrentGame>) {
+    game.progression = Some(settings.progression());
+}
+
+// Runs on the lock that takes the line count past the goal. The clear is
+// recorded either way; endless marathon announces it and plays on until
+// the stack tops out.
+#[allow(clippy::too_many_arguments)]
+pub fn finish_marathon(
+    mut locked_events: EventReader<LockEvent>,
+    settings: Res<MarathonSettings>,
+    clock: Res<GameClock>,
+    game: Res<CurrentGame>,
+    high_scores: Res<HighScores>,
+    mut bests: ResMut<BestTimes>,
+    mut results: ResMut<GameResults>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut fields: Query<(&HudField, &mut Text)>,
+) {
+    let lines_now: u32 = locked_events.read().map(|event| event.lines_cleared).sum();
+    let lines_before = game.lines_cleared.saturating_sub(lines_now);
+    if lines_before >= settings.line_goal || game.lines_cleared < settings.line_goal {
+        return;
+    }
+    let run = TimedRun {
+        time: clock.elapsed,
+        score: game.score.value,
+        pieces: game.pieces_placed,
+        seed: game.rng.seed(),
+    };
+    let best_line = if bests.submit(MARATHON.id, settings.line_goal, run) {
+        "New best clear!".to_string()
+    } else {
+        let best = bests
+            .get(MARATHON.id, settings.line_goal)
+            .map_or(run.time, |best| best.time);
+        format!("Best clear: {}", format_time(best.as_secs_f32()))
+    };
+    if settings.endless {
+        set_field(
+            &mut fields,
+            HudField::Banner,
+            format!(
+                "{} lines in {}",
+                settings.line_goal,
+                format_time(clock.seconds())
+            ),
+        );
+        return;
+    }
+
+    let rank = high_scores
+        .rank_of(MARATHON.id, game.score.value)
+        .map_or("Not ranked".to_string(), |rank| {
+            format!("Rank {}", rank + 1)
+        });
+    *results = GameResults {
+        headline: "Marathon Complete".to_string(),
+        lines: vec![
+            format!("Score: {}", game.score.value),
+            format!("Level: {}", game.level.value),
+            format!("Lines: {}", game.lines_cleared),
+            format!("Time: {}", format_time(clock.seconds())),
+            format!("PPS: {:.2}", clock.pieces_per_second(game.pieces_placed)),
+            rank,
+            best_line,
+        ],
+    };
+    next_state.set(GameState::Finished);
+}
+
+// Best scores, then the fastest clear of each line goal.
+pub fn marathon_table(world: &World, mode: &'static str) -> Vec<String> {
+    let mut lines = score_table(world, mode);
+    lines.extend(best_time_table(world, mode, &MARATHON_GOALS));
+    lines
+}
+
+pub struct MarathonModePlugin;
+
+impl Plugin for MarathonModePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SelectedMode>()
+            .init_resource::<MarathonSettings>()
+            .init_resource::<HighScores>()
+            .init_resource::<BestTimes>()
+            .init_resource::<GameResults>()
+            .register_game_mode(MARATHON)
+            .register_setting(GOAL_SETTING)
+            .register_setting(LEVEL_CAP_SETTING)
+            .register_setting(LEVEL_GOAL_SETTING)
+            .register_setting(ENDLESS_SETTING)
+            .register_leaderboard(
+                MARATHON.id,
+                Leaderboard {
+                    ranking: Ranking::Score,
+                    render: marathon_table,
+                },
+            )
+            .add_systems(
+                OnEnter(GameState::Playing),
+                setup_marathon
+                    .before(start_game)
+                    .run_if(mode_is(MARATHON.id).and(fresh_game)),
+            )
+            .add_systems(
+                Update,
+                finish_marathon
+                    .after(step_game)
+                    .run_if(in_state(GameState::Playing).and(mode_is(MARATHON.id))),
+            );
+    }
+}
@@ -0,0 +1,209 @@
+use bevy::prelude::*;
+
+use crate::game_core::entry_delay::EntryDelay;
+use crate::game_core::gravity::FRAME_RATE;
+use crate::game_core::lock_delay::LockDelay;
+use crate::game_core::progression::MAX_LEVEL;
+use crate::resources::{CurrentGame, LockEvent};
+use crate::state::{GameResults, GameState};
+use crate::stats::{GameClock, format_time};
+use crate::systems::game_flow::{spawn_game_over_screen, start_game};
+use crate::systems::movement::step_game;
+
+use super::{GameModeInfo, RegisterGameMode, SelectedMode, fresh_game, mode_is};
+
+pub const MASTER: GameModeInfo = GameModeInfo {
+    id: "master",
+    name: "Master",
+    description: "20G from the first piece, graded on the way to level 999",
+};
+
+pub const MAX_MASTER_LEVEL: u32 = 999;
+
+// Delays for one 100-level section, in frames at 60 fps.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SectionTiming {
+    pub are: u32,
+    pub line_clear: u32,
+    pub lock: u32,
+}
+
+const fn section(are: u32, line_clear: u32, lock: u32) -> SectionTiming {
+    SectionTiming {
+        are,
+        line_clear,
+        lock,
+    }
+}
+
+pub const SECTIONS: [SectionTiming; 10] = [
+    section(25, 40, 30),
+    section(25, 25, 30),
+    section(25, 16, 30),
+    section(16, 12, 30),
+    section(12, 6, 30),
+    section(12, 6, 26),
+    section(12, 6, 22),
+    section(6, 6, 18),
+    section(6, 6, 15),
+    section(6, 6, 15),
+];
+
+pub fn section_timing(level: u32) -> SectionTiming {
+    SECTIONS[(level as usize / 100).min(SECTIONS.len() - 1)]
+}
+
+impl SectionTiming {
+    pub fn apply(&self, lock_delay: &mut LockDelay, entry_delay: &mut EntryDelay) {
+        lock_delay.delay = self.lock as f32 / FRAME_RATE;
+        entry_delay.are = self.are as f32 / FRAME_RATE;
+        entry_delay.line_clear = self.line_clear as f32 / FRAME_RATE;
+    }
+}
+
+// Each piece moves the level up by one, except at the end of a section
+// (x99) and at 998, where only clearing lines gets past.
+pub fn advance_level(level: u32, lines: u32) -> u32 {
+    let stopped = level % 100 == 99 || level == MAX_MASTER_LEVEL - 1;
+    let piece = if stopped { 0 } else { 1 };
+    (level + piece + lines).min(MAX_MASTER_LEVEL)
+}
+
+pub const GRADES: [&str; 19] = [
+    "9", "8", "7", "6", "5", "4", "3", "2", "1", "S1", "S2", "S3", "S4", "S5", "S6", "S7", "S8",
+    "S9", "GM",
+];
+const TOP_PLAYED_GRADE: usize = GRADES.len() - 2;
+const GRADE_POINTS: f32 = 100.0;
+const CLEAR_POINTS: [f32; 4] = [10.0, 25.0, 45.0, 70.0];
+
+// Hidden while playing and shown on the results screen. Clears earn grade
+// points, worth more at higher levels and less at higher grades, and the
+// points drain away while a piece is in play.
+#[derive(Resource, Debug, Default, Clone, PartialEq)]
+pub struct MasterGrade {
+    pub grade: usize,
+    pub points: f32,
+}
+
+impl MasterGrade {
+    pub fn award(&mut self, lines: u32, level: u32) {
+        if lines == 0 || self.grade >= TOP_PLAYED_GRADE {
+            return;
+        }
+        let base = CLEAR_POINTS[(lines as usize).min(4) - 1];
+        let level_bonus = 1.0 + level as f32 / 250.0;
+        self.points += base * level_bonus / (1.0 + self.grade as f32 * 0.25);
+        while self.points >= GRADE_POINTS && self.grade < TOP_PLAYED_GRADE {
+            self.points -= GRADE_POINTS;
+            self.grade += 1;
+        }
+    }
+
+    pub fn decay(&mut self, dt: f32) {
+        let rate = 2.0 + self.grade as f32 * 0.5;
+        self.points = (self.points - rate * dt).max(0.0);
+    }
+
+    // The last grade only goes to a run that reaches 999 at S9.
+    pub fn finish(&mut self, level: u32) {
+        if level >= MAX_MASTER_LEVEL && self.grade == TOP_PLAYED_GRADE {
+            self.grade += 1;
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        GRADES[self.grade]
+    }
+}
+
+pub fn setup_master(mut game: ResMut<CurrentGame>, mut grade: ResMut<MasterGrade>) {
+    let game = &mut **game;
+    game.level.value = 0;
+    // the guideline curve is past 20G at its top level
+    game.gravity.fix_level(MAX_LEVEL);
+    section_timing(0).apply(&mut game.lock_delay, &mut game.entry_delay);
+    *grade = MasterGrade::default();
+}
+
+pub fn advance_master(
+    mut locked_events: EventReader<LockEvent>,
+    mut game: ResMut<CurrentGame>,
+    mut grade: ResMut<MasterGrade>,
+) {
+    let game = &mut **game;
+    for event in locked_events.read() {
+        let before = game.level.value;
+        game.level.value = advance_level(before, event.lines_cleared);
+        grade.award(event.lines_cleared, game.level.value);
+        if game.level.value / 100 != before / 100 {
+            section_timing(game.level.value).apply(&mut game.lock_delay, &mut game.entry_delay);
+        }
+    }
+}
+
+pub fn decay_grade(time: Res<Time>, game: Res<CurrentGame>, mut grade: ResMut<MasterGrade>) {
+    if game.active_piece().is_some() && grade.points > 0.0 {
+        grade.decay(time.delta_secs());
+    }
+}
+
+pub fn finish_master(
+    game: Res<CurrentGame>,
+    clock: Res<GameClock>,
+    mut grade: ResMut<MasterGrade>,
+    mut results: ResMut<GameResults>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if game.level.value < MAX_MASTER_LEVEL {
+        return;
+    }
+    grade.finish(game.level.value);
+    *results = GameResults {
+        headline: format!("Grade {}", grade.label()),
+        lines: vec![
+            format!("Level: {}", game.level.value),
+            format!("Time: {}", format_time(clock.seconds())),
+            format!("Score: {}", game.score.value),
+            format!("Lines: {}", game.lines_cleared),
+        ],
+    };
+    next_state.set(GameState::Finished);
+}
+
+// A top out still shows the grade reached, on the game over screen.
+pub fn report_master_grade(grade: Res<MasterGrade>, mut results: ResMut<GameResults>) {
+    results.lines = vec![format!("Grade: {}", grade.label())];
+}
+
+pub struct MasterModePlugin;
+
+impl Plugin for MasterModePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SelectedMode>()
+            .init_resource::<MasterGrade>()
+            .init_resource::<GameResults>()
+            .register_game_mode(MASTER)
+            .add_systems(
+                OnEnter(GameState::Playing),
+                setup_master
+                    .before(start_game)
+                    .run_if(mode_is(MASTER.id).and(fresh_game)),
+            )
+            .add_systems(
+                OnEnter(GameState::GameOver),
+                report_master_grade
+                    .before(spawn_game_over_screen)
+                    .run_if(mode_is(MASTER.id)),
+            )
+            .add_systems(
+                Update,
+                (
+                    advance_master.after(step_game),
+                    decay_grade,
+                    finish_master.after(advance_master),
+                )
+                    .run_if(in_state(GameState::Playing).and(mode_is(MASTER.id))),
+            );
+    }
+}
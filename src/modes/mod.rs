@@ -0,0 +1,192 @@
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+use crate::game_core::progression::LevelProgression;
+use crate::records::score_table;
+use crate::resources::CurrentGame;
+use crate::state::GameState;
+use crate::systems::game_flow::start_game;
+
+pub mod dig;
+pub mod marathon;
+pub mod master;
+pub mod puzzle;
+pub mod sprint;
+pub mod ultra;
+
+// Game modes are plugins: each one registers a `GameModeInfo` so it shows
+// up in mode select, adds its own lines to the settings screen, and gates
+// its own systems with `mode_is`. Modes that level up set the game's
+// progression when it starts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GameModeInfo {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+}
+
+// How a mode's best games are ranked on the high scores screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Ranking {
+    #[default]
+    Score,
+    Time,
+}
+
+impl Ranking {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Ranking::Score => "by score",
+            Ranking::Time => "by time",
+        }
+    }
+}
+
+// The lines a mode shows under its name on the high scores screen, read
+// from whatever records the mode keeps. Modes that don't register one get
+// the score table.
+#[derive(Debug, Clone, Copy)]
+pub struct Leaderboard {
+    pub ranking: Ranking,
+    pub render: fn(&World, &'static str) -> Vec<String>,
+}
+
+impl Default for Leaderboard {
+    fn default() -> Self {
+        Leaderboard {
+            ranking: Ranking::Score,
+            render: score_table,
+        }
+    }
+}
+
+// One line on the settings screen: `label` reads the value from the world
+// and `adjust` steps it left or right, keeping it in a playable range.
+#[derive(Debug, Clone, Copy)]
+pub struct Setting {
+    pub label: fn(&World) -> String,
+    pub adjust: fn(&mut World, isize),
+}
+
+#[derive(Resource, Debug, Default)]
+pub struct GameModes {
+    modes: Vec<GameModeInfo>,
+    leaderboards: HashMap<&'static str, Leaderboard>,
+    settings: Vec<Setting>,
+}
+
+impl GameModes {
+    pub fn register(&mut self, info: GameModeInfo) {
+        match self.modes.iter_mut().find(|mode| mode.id == info.id) {
+            Some(existing) => *existing = info,
+            None => self.modes.push(info),
+        }
+    }
+
+    pub fn set_leaderboard(&mut self, id: &'static str, leaderboard: Leaderboard) {
+        self.leaderboards.insert(id, leaderboard);
+    }
+
+    pub fn leaderboard(&self, id: &str) -> Leaderboard {
+        self.leaderboards.get(id).copied().unwrap_or_default()
+    }
+
+    pub fn add_setting(&mut self, setting: Setting) {
+        self.settings.push(setting);
+    }
+
+    pub fn settings(&self) -> &[Setting] {
+        &self.settings
+    }
+
+    pub fn get(&self, id: &str) -> Option<&GameModeInfo> {
+        self.modes.iter().find(|mode| mode.id == id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &GameModeInfo> {
+        self.modes.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.modes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.modes.is_empty()
+    }
+}
+
+pub trait RegisterGameMode {
+    fn register_game_mode(&mut self, info: GameModeInfo) -> &mut Self;
+
+    fn register_leaderboard(&mut self, id: &'static str, leaderboard: Leaderboard) -> &mut Self;
+
+    fn register_setting(&mut self, setting: Setting) -> &mut Self;
+}
+
+impl RegisterGameMode for App {
+    fn register_game_mode(&mut self, info: GameModeInfo) -> &mut Self {
+        self.world_mut()
+            .get_resource_or_init::<GameModes>()
+            .register(info);
+        self
+    }
+
+    fn register_leaderboard(&mut self, id: &'static str, leaderboard: Leaderboard) -> &mut Self {
+        self.world_mut()
+            .get_resource_or_init::<GameModes>()
+            .set_leaderboard(id, leaderboard);
+        self
+    }
+
+    fn register_setting(&mut self, setting: Setting) -> &mut Self {
+        self.world_mut()
+            .get_resource_or_init::<GameModes>()
+            .add_setting(setting);
+        self
+    }
+}
+
+pub const ENDLESS: GameModeInfo = GameModeInfo {
+    id: "endless",
+    name: "Endless",
+    description: "Play until you top out",
+};
+
+#[derive(Resource, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SelectedMode(pub &'static str);
+
+impl Default for SelectedMode {
+    fn default() -> Self {
+        SelectedMode(ENDLESS.id)
+    }
+}
+
+pub fn mode_is(id: &'static str) -> impl Fn(Res<SelectedMode>) -> bool + Clone {
+    move |selected: Res<SelectedMode>| selected.0 == id
+}
+
+// Resuming from pause also enters `Playing`; mode setups only run for a
+// game that has not started yet.
+pub fn fresh_game(game: Res<CurrentGame>) -> bool {
+    !game.is_started()
+}
+
+pub fn setup_endless(mut game: ResMut<CurrentGame>) {
+    game.progression = Some(LevelProgression::default());
+}
+
+pub struct EndlessModePlugin;
+
+impl Plugin for EndlessModePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SelectedMode>()
+            .register_game_mode(ENDLESS)
+            .add_systems(
+                OnEnter(GameState::Playing),
+                setup_endless
+                    .before(start_game)
+                    .run_if(mode_is(ENDLESS.id).and(fresh_game)),
+            );
+    }
+}
@@ -0,0 +1,372 @@
+use bevy::prelude::*;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::game_core::board::{GameMap, PieceType, Presence};
+use crate::game_core::piece::Piece;
+use crate::game_core::randomizer::{PieceRandomizer, SequenceRandomizer};
+use crate::game_core::tspin::{PieceLocked, TSpin};
+use crate::resources::{CurrentGame, LockEvent};
+use crate::state::{GameResults, GameState};
+use crate::systems::game_flow::start_game;
+use crate::systems::movement::step_game;
+
+use super::dig::GARBAGE_COLOR;
+use super::{GameModeInfo, RegisterGameMode, SelectedMode, Setting, fresh_game, mode_is};
+
+pub const PUZZLE: GameModeInfo = GameModeInfo {
+    id: "puzzle",
+    name: "Puzzle",
+    description: "Reach the goal with a set board and queue",
+};
+
+// Puzzles are loaded from `*.puzzle` files in this directory, in file name
+// order, unless `--puzzles <dir>` points somewhere else. A file looks like:
+//
+//     # comments start with '#'
+//     name: Tetris
+//     goal: lines 4
+//     queue: I
+//     board:
+//     XXXXXXXXX.
+//     XXXXXXXXX.
+//
+// Goals are `perfect clear`, `tspin single|double|triple` and `lines N`.
+// Board rows sit at the bottom of the field: `.` is empty, `X` is garbage
+// and a piece letter is a block of that piece's color.
+pub const PUZZLE_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/puzzles");
+pub const PUZZLE_EXTENSION: &str = "puzzle";
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PuzzleGoal {
+    PerfectClear,
+    TSpin(u32),
+    Lines(u32),
+}
+
+const TSPIN_NAMES: [&str; 3] = ["single", "double", "triple"];
+
+impl PuzzleGoal {
+    fn parse(text: &str) -> Option<Self> {
+        let words: Vec<&str> = text.split_whitespace().collect();
+        match words.as_slice() {
+            ["perfect", "clear"] => Some(PuzzleGoal::PerfectClear),
+            ["tspin", name] => {
+                let lines = TSPIN_NAMES.iter().position(|tspin| tspin == name)?;
+                Some(PuzzleGoal::TSpin(lines as u32 + 1))
+            }
+            ["lines", count] => count
+                .parse()
+                .ok()
+                .filter(|&lines| lines > 0)
+                .map(PuzzleGoal::Lines),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            PuzzleGoal::PerfectClear => "Perfect clear".to_string(),
+            PuzzleGoal::TSpin(lines) => {
+                let name = lines
+                    .checked_sub(1)
+                    .and_then(|index| TSPIN_NAMES.get(index as usize))
+                    .unwrap_or(&"zero");
+                format!("T-spin {name}")
+            }
+            PuzzleGoal::Lines(1) => "Clear 1 line".to_string(),
+            PuzzleGoal::Lines(lines) => format!("Clear {lines} lines"),
+        }
+    }
+
+    // `lines_cleared` is the total for the attempt, including this lock.
+    pub fn is_met(&self, locked: &PieceLocked, lines_cleared: u32) -> bool {
+        match *self {
+            PuzzleGoal::PerfectClear => locked.perfect_clear,
+            PuzzleGoal::TSpin(lines) => {
+                locked.tspin == TSpin::Full && locked.lines_cleared == lines
+            }
+            PuzzleGoal::Lines(lines) => lines_cleared >= lines,
+        }
+    }
+}
+
+fn piece_from_letter(letter: char) -> Option<PieceType> {
+    match letter {
+        'I' => Some(PieceType::I),
+        'J' => Some(PieceType::J),
+        'L' => Some(PieceType::L),
+        'O' => Some(PieceType::O),
+        'S' => Some(PieceType::S),
+        'T' => Some(PieceType::T),
+        'Z' => Some(PieceType::Z),
+        _ => None,
+    }
+}
+
+fn parse_cell(letter: char) -> Option<Presence> {
+    match letter {
+        '.' => Some(Presence::No),
+        'X' => Some(Presence::Yes(GARBAGE_COLOR)),
+        _ => {
+            piece_from_letter(letter).map(|piece_type| Presence::Yes(Piece::from(piece_type).color))
+        }
+    }
+}
+
+// One puzzle: the board to start from, the pieces to play in order and the
+// goal to reach before they run out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Puzzle {
+    pub name: String,
+    pub goal: PuzzleGoal,
+    pub queue: Vec<PieceType>,
+    pub board: Vec<Vec<Presence>>,
+}
+
+impl Puzzle {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut name = None;
+        let mut goal = None;
+        let mut queue = Vec::new();
+        let mut board = Vec::new();
+        let mut in_board = false;
+
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            let error = |message: &str| format!("line {}: {message}", number + 1);
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if in_board {
+                let row = line
+                    .chars()
+                    .map(parse_cell)
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or_else(|| error("unknown board cell"))?;
+                if board
+                    .first()
+                    .is_some_and(|first: &Vec<Presence>| first.len() != row.len())
+                {
+                    return Err(error("board rows differ in width"));
+                }
+                board.push(row);
+                continue;
+            }
+            let (key, value) = line
+                .split_once(':')
+                .ok_or_else(|| error("expected `key: value`"))?;
+            let value = value.trim();
+            match key.trim() {
+                "name" => name = Some(value.to_string()),
+                "goal" => {
+                    goal = Some(PuzzleGoal::parse(value).ok_or_else(|| error("unknown goal"))?)
+                }
+                "queue" => {
+                    queue = value
+                        .chars()
+                        .filter(|letter| !letter.is_whitespace())
+                        .map(piece_from_letter)
+                        .collect::<Option<Vec<_>>>()
+                        .ok_or_else(|| error("unknown piece in queue"))?;
+                }
+                "board" => in_board = true,
+                _ => return Err(error("unknown key")),
+            }
+        }
+
+        if queue.is_empty() {
+            return Err("the queue has no pieces".to_string());
+        }
+        Ok(Puzzle {
+            name: name.ok_or("missing name")?,
+            goal: goal.ok_or("missing goal")?,
+            queue,
+            board,
+        })
+    }
+
+    // Writes the board into the bottom rows of the map. Anything wider or
+    // taller than the map is cut off.
+    pub fn load_board(&self, game_map: &mut GameMap) {
+        let bottom = game_map.height() - self.board.len().min(game_map.height());
+        let rows = self.board.iter().rev().take(game_map.height()).rev();
+        for (y, row) in (bottom..).zip(rows) {
+            for (x, &cell) in row.iter().enumerate().take(game_map.width()) {
+                game_map.set(x, y, cell);
+            }
+        }
+    }
+}
+
+#[derive(Resource, Debug, Clone, PartialEq, Eq)]
+pub struct PuzzleDir(pub PathBuf);
+
+impl Default for PuzzleDir {
+    fn default() -> Self {
+        PuzzleDir(PathBuf::from(PUZZLE_DIR))
+    }
+}
+
+#[derive(Resource, Debug, Default, Clone)]
+pub struct PuzzlePack {
+    puzzles: Vec<Puzzle>,
+    selected: usize,
+}
+
+impl PuzzlePack {
+    pub fn new(puzzles: Vec<Puzzle>) -> Self {
+        PuzzlePack {
+            puzzles,
+            selected: 0,
+        }
+    }
+
+    // Reads every puzzle file in `dir`, skipping the ones that don't parse.
+    pub fn load(dir: &Path) -> Self {
+        let Ok(entries) = fs::read_dir(dir) else {
+            println!("No puzzle pack found at {}", dir.display());
+            return Self::default();
+        };
+        let mut paths: Vec<_> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == PUZZLE_EXTENSION))
+            .collect();
+        paths.sort();
+
+        let mut puzzles = Vec::new();
+        for path in paths {
+            match fs::read_to_string(&path)
+                .map_err(|error| error.to_string())
+                .and_then(|text| Puzzle::parse(&text))
+            {
+                Ok(puzzle) => puzzles.push(puzzle),
+                Err(error) => println!("Skipping puzzle {}: {error}", path.display()),
+            }
+        }
+        Self::new(puzzles)
+    }
+
+    pub fn len(&self) -> usize {
+        self.puzzles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.puzzles.is_empty()
+    }
+
+    pub fn selected(&self) -> Option<&Puzzle> {
+        self.puzzles.get(self.selected)
+    }
+
+    pub fn step(&mut self, direction: isize) {
+        if self.puzzles.is_empty() {
+            return;
+        }
+        let index = (self.selected as isize + direction).clamp(0, self.puzzles.len() as isize - 1);
+        self.selected = index as usize;
+    }
+
+    pub fn label(&self) -> String {
+        match self.selected() {
+            Some(puzzle) => format!("{}/{} {}", self.selected + 1, self.len(), puzzle.name),
+            None => "none".to_string(),
+        }
+    }
+}
+
+const PUZZLE_SETTING: Setting = Setting {
+    label: |world| format!("Puzzle: {}", world.resource::<PuzzlePack>().label()),
+    adjust: |world, direction| world.resource_mut::<PuzzlePack>().step(direction),
+};
+
+pub fn load_puzzle_pack(dir: Res<PuzzleDir>, mut pack: ResMut<PuzzlePack>) {
+    *pack = PuzzlePack::load(&dir.0);
+}
+
+// Retrying goes through `reset_game`, so the board and queue start over
+// each time.
+pub fn setup_puzzle(
+    pack: Res<PuzzlePack>,
+    dir: Res<PuzzleDir>,
+    mut game: ResMut<CurrentGame>,
+    mut results: ResMut<GameResults>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let Some(puzzle) = pack.selected() else {
+        *results = GameResults {
+            headline: "No Puzzles".to_string(),
+            lines: vec![format!(
+                "Add .{PUZZLE_EXTENSION} files to {}",
+                dir.0.display()
+            )],
+        };
+        next_state.set(GameState::Finished);
+        return;
+    };
+    puzzle.load_board(&mut game.game_map);
+    game.randomizer = PieceRandomizer(Box::new(SequenceRandomizer::new(puzzle.queue.clone())));
+}
+
+// Passes on the lock that reaches the goal and fails once the queue is used
+// up without it. A piece left in hold counts as used: there is nothing to
+// swap it for.
+pub fn judge_puzzle(
+    mut locked_events: EventReader<LockEvent>,
+    pack: Res<PuzzlePack>,
+    game: Res<CurrentGame>,
+    mut results: ResMut<GameResults>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let Some(puzzle) = pack.selected() else {
+        return;
+    };
+    let solved = locked_events
+        .read()
+        .any(|event| puzzle.goal.is_met(event, game.lines_cleared));
+    let out_of_pieces =
+        game.pieces_placed + game.held_piece().is_some() as u32 >= puzzle.queue.len() as u32;
+    if !solved && !out_of_pieces {
+        return;
+    }
+    *results = GameResults {
+        headline: if solved {
+            "Puzzle Solved"
+        } else {
+            "Puzzle Failed"
+        }
+        .to_string(),
+        lines: vec![
+            puzzle.name.clone(),
+            format!("Goal: {}", puzzle.goal.label()),
+            format!("Pieces: {}/{}", game.pieces_placed, puzzle.queue.len()),
+        ],
+    };
+    next_state.set(GameState::Finished);
+}
+
+pub struct PuzzleModePlugin;
+
+impl Plugin for PuzzleModePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SelectedMode>()
+            .init_resource::<PuzzleDir>()
+            .init_resource::<PuzzlePack>()
+            .init_resource::<GameResults>()
+            .register_game_mode(PUZZLE)
+            .register_setting(PUZZLE_SETTING)
+            .add_systems(Startup, load_puzzle_pack)
+            .add_systems(
+                OnEnter(GameState::Playing),
+                setup_puzzle
+                    .before(start_game)
+                    .run_if(mode_is(PUZZLE.id).and(fresh_game)),
+            )
+            .add_systems(
+                Update,
+                judge_puzzle
+                    .after(step_game)
+                    .run_if(in_state(GameState::Playing).and(mode_is(PUZZLE.id))),
+            );
+    }
+}
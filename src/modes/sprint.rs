@@ -0,0 +1,121 @@
+use bevy::prelude::*;
+
+use crate::finesse::judge_finesse;
+use crate::records::{BestTimes, TimedRun, best_time_table};
+use crate::resources::CurrentGame;
+use crate::state::{GameResults, GameState};
+use crate::stats::{GameClock, GameStats, count_keys, format_time, tick_clock};
+use crate::systems::movement::step_game;
+
+use super::{GameModeInfo, Leaderboard, Ranking, RegisterGameMode, SelectedMode, Setting, mode_is};
+
+pub const SPRINT: GameModeInfo = GameModeInfo {
+    id: "sprint",
+    name: "Sprint",
+    description: "Clear the line goal against the clock",
+};
+
+pub const SPRINT_GOALS: [u32; 3] = [20, 40, 100];
+
+#[derive(Resource, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SprintGoal(pub u32);
+
+impl Default for SprintGoal {
+    fn default() -> Self {
+        SprintGoal(40)
+    }
+}
+
+impl SprintGoal {
+    // Moves to the neighbouring goal, stopping at the shortest and longest.
+    pub fn step(&mut self, direction: isize) {
+        let index = SPRINT_GOALS
+            .iter()
+            .position(|&goal| goal == self.0)
+            .unwrap_or(1) as isize;
+        let index = (index + direction).clamp(0, SPRINT_GOALS.len() as isize - 1);
+        self.0 = SPRINT_GOALS[index as usize];
+    }
+}
+
+const GOAL_SETTING: Setting = Setting {
+    label: |world| format!("Sprint goal: {} lines", world.resource::<SprintGoal>().0),
+    adjust: |world, direction| world.resource_mut::<SprintGoal>().step(direction),
+};
+
+// The clock stops with the lock that reaches the goal. Lines come from the
+// game's running total, which keeps counting where `Level` resets every
+// level.
+pub fn finish_sprint(
+    stats: Res<GameStats>,
+    clock: Res<GameClock>,
+    goal: Res<SprintGoal>,
+    game: Res<CurrentGame>,
+    mut bests: ResMut<BestTimes>,
+    mut results: ResMut<GameResults>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if game.lines_cleared < goal.0 {
+        return;
+    }
+    let run = TimedRun {
+        time: clock.elapsed,
+        score: game.score.value,
+        pieces: game.pieces_placed,
+        seed: game.rng.seed(),
+    };
+    let best_line = if bests.submit(SPRINT.id, goal.0, run) {
+        "New personal best!".to_string()
+    } else {
+        let best = bests
+            .get(SPRINT.id, goal.0)
+            .map_or(run.time, |best| best.time);
+        format!("Personal best: {}", format_time(best.as_secs_f32()))
+    };
+    *results = GameResults {
+        headline: format!("{} Lines", goal.0),
+        lines: vec![
+            format!("Time: {}", format_time(clock.seconds())),
+            format!("Pieces: {}", game.pieces_placed),
+            format!("PPS: {:.2}", clock.pieces_per_second(game.pieces_placed)),
+            format!("KPP: {:.2}", stats.keys_per_piece(game.pieces_placed)),
+            format!("Finesse faults: {}", stats.finesse_faults),
+            best_line,
+        ],
+    };
+    next_state.set(GameState::Finished);
+}
+
+// Sprint is ranked by time, one best per line goal.
+pub fn sprint_table(world: &World, mode: &'static str) -> Vec<String> {
+    best_time_table(world, mode, &SPRINT_GOALS)
+}
+
+pub struct SprintModePlugin;
+
+impl Plugin for SprintModePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SelectedMode>()
+            .init_resource::<SprintGoal>()
+            .init_resource::<BestTimes>()
+            .init_resource::<GameResults>()
+            .register_game_mode(SPRINT)
+            .register_setting(GOAL_SETTING)
+            .register_leaderboard(
+                SPRINT.id,
+                Leaderboard {
+                    ranking: Ranking::Time,
+                    render: sprint_table,
+                },
+            )
+            .add_systems(
+                Update,
+                finish_sprint
+                    .after(step_game)
+                    .after(count_keys)
+                    .after(tick_clock)
+                    .after(judge_finesse)
+                    .run_if(in_state(GameState::Playing).and(mode_is(SPRINT.id))),
+            );
+    }
+}
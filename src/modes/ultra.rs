@@ -0,0 +1,166 @@
+use bevy::prelude::*;
+use std::time::Duration;
+
+use crate::finesse::judge_finesse;
+use crate::game_core::progression::MAX_LEVEL;
+use crate::records::HighScores;
+use crate::resources::CurrentGame;
+use crate::state::{GameResults, GameState};
+use crate::stats::{GameClock, GameStats, count_keys, format_time, tick_clock};
+use crate::systems::game_flow::start_game;
+use crate::systems::hud::{HudField, set_field, update_clock_hud};
+use crate::systems::movement::step_game;
+
+use super::{GameModeInfo, RegisterGameMode, SelectedMode, Setting, fresh_game, mode_is};
+
+pub const ULTRA: GameModeInfo = GameModeInfo {
+    id: "ultra",
+    name: "Ultra",
+    description: "Score as much as you can before time runs out",
+};
+
+pub const ULTRA_DURATIONS: [u64; 4] = [60, 120, 180, 300];
+
+#[derive(Resource, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct UltraSettings {
+    pub seconds: u64,
+    // gravity stays at this level for the whole game
+    pub level: u32,
+}
+
+impl Default for UltraSettings {
+    fn default() -> Self {
+        UltraSettings {
+            seconds: 120,
+            level: 1,
+        }
+    }
+}
+
+impl UltraSettings {
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs(self.seconds)
+    }
+
+    pub fn step_duration(&mut self, direction: isize) {
+        let index = ULTRA_DURATIONS
+            .iter()
+            .position(|&seconds| seconds == self.seconds)
+            .unwrap_or(1) as isize;
+        let index = (index + direction).clamp(0, ULTRA_DURATIONS.len() as isize - 1);
+        self.seconds = ULTRA_DURATIONS[index as usize];
+    }
+
+    pub fn step_level(&mut self, direction: isize) {
+        self.level = (self.level as isize + direction).clamp(1, MAX_LEVEL as isize) as u32;
+    }
+}
+
+// Whole minutes and seconds, as shown in settings.
+pub fn format_duration(seconds: u64) -> String {
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+const DURATION_SETTING: Setting = Setting {
+    label: |world| {
+        let seconds = world.resource::<UltraSettings>().seconds;
+        format!("Ultra time: {}", format_duration(seconds))
+    },
+    adjust: |world, direction| {
+        world
+            .resource_mut::<UltraSettings>()
+            .step_duration(direction)
+    },
+};
+
+const LEVEL_SETTING: Setting = Setting {
+    label: |world| {
+        format!(
+            "Ultra speed: level {}",
+            world.resource::<UltraSettings>().level
+        )
+    },
+    adjust: |world, direction| world.resource_mut::<UltraSettings>().step_level(direction),
+};
+
+pub fn fix_ultra_gravity(settings: Res<UltraSettings>, mut game: ResMut<CurrentGame>) {
+    game.gravity.fix_level(settings.level);
+}
+
+pub fn show_time_left(
+    clock: Res<GameClock>,
+    settings: Res<UltraSettings>,
+    mut fields: Query<(&HudField, &mut Text)>,
+) {
+    let left = settings.duration().saturating_sub(clock.elapsed);
+    set_field(
+        &mut fields,
+        HudField::Time,
+        format!("Time left: {}", format_time(left.as_secs_f32())),
+    );
+}
+
+pub fn finish_ultra(
+    clock: Res<GameClock>,
+    settings: Res<UltraSettings>,
+    game: Res<CurrentGame>,
+    stats: Res<GameStats>,
+    high_scores: Res<HighScores>,
+    mut results: ResMut<GameResults>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if clock.elapsed < settings.duration() {
+        return;
+    }
+    // the score goes on the table as the results screen opens
+    let rank = high_scores
+        .rank_of(ULTRA.id, game.score.value)
+        .map_or("Not ranked".to_string(), |rank| {
+            format!("Rank {}", rank + 1)
+        });
+    *results = GameResults {
+        headline: "Time's Up".to_string(),
+        lines: vec![
+            format!("Score: {}", game.score.value),
+            format!("Lines: {}", game.lines_cleared),
+            format!("Pieces: {}", game.pieces_placed),
+            format!("PPS: {:.2}", clock.pieces_per_second(game.pieces_placed)),
+            format!("KPP: {:.2}", stats.keys_per_piece(game.pieces_placed)),
+            format!("Finesse faults: {}", stats.finesse_faults),
+            rank,
+        ],
+    };
+    next_state.set(GameState::Finished);
+}
+
+pub struct UltraModePlugin;
+
+impl Plugin for UltraModePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SelectedMode>()
+            .init_resource::<UltraSettings>()
+            .init_resource::<HighScores>()
+            .init_resource::<GameResults>()
+            .register_game_mode(ULTRA)
+            .register_setting(DURATION_SETTING)
+            .register_setting(LEVEL_SETTING)
+            .add_systems(
+                OnEnter(GameState::Playing),
+                fix_ultra_gravity
+                    .before(start_game)
+                    .run_if(mode_is(ULTRA.id).and(fresh_game)),
+            )
+            .add_systems(
+                Update,
+                (
+                    show_time_left.after(update_clock_hud),
+                    finish_ultra
+                        .after(step_game)
+                        .after(tick_clock)
+                        .after(count_keys)
+                        .after(judge_finesse),
+                )
+                    .run_if(in_state(GameState::Playing).and(mode_is(ULTRA.id))),
+            );
+    }
+}
@@ -0,0 +1,287 @@
+use bevy::prelude::*;
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::modes::{GameModes, SelectedMode};
+use crate::resources::CurrentGame;
+use crate::stats::format_time;
+
+pub const MAX_HIGH_SCORES: usize = 10;
+pub const MAX_REPLAYS: usize = 20;
+// High scores listed per mode on the high scores screen.
+pub const SHOWN_PER_MODE: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScoreEntry {
+    pub score: u32,
+    pub level: u32,
+    pub seed: u64,
+}
+
+// Best scores per mode for this session, highest first.
+#[derive(Resource, Debug, Default)]
+pub struct HighScores(HashMap<&'static str, Vec<ScoreEntry>>);
+
+impl HighScores {
+    // The rank a score would take, if it makes the table.
+    pub fn rank_of(&self, mode: &str, score: u32) -> Option<usize> {
+        let rank = self
+            .for_mode(mode)
+            .partition_point(|existing| existing.score >= score);
+        (rank < MAX_HIGH_SCORES).then_some(rank)
+    }
+
+    // Returns the entry's rank when it makes the table.
+    pub fn submit(&mut self, mode: &'static str, entry: ScoreEntry) -> Option<usize> {
+        let rank = self.rank_of(mode, entry.score)?;
+        let table = self.0.entry(mode).or_default();
+        table.insert(rank, entry);
+        table.truncate(MAX_HIGH_SCORES);
+        Some(rank)
+    }
+
+    pub fn for_mode(&self, mode: &str) -> &[ScoreEntry] {
+        self.0.get(mode).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, &ScoreEntry)> {
+        self.0
+            .iter()
+            .flat_map(|(&mode, table)| table.iter().map(move |entry| (mode, entry)))
+    }
+}
+
+// The default leaderboard: a mode's best scores.
+pub fn score_table(world: &World, mode: &'static str) -> Vec<String> {
+    let table = world
+        .get_resource::<HighScores>()
+        .map_or(&[][..], |high_scores| high_scores.for_mode(mode));
+    if table.is_empty() {
+        return vec!["  no scores yet".to_string()];
+    }
+    table
+        .iter()
+        .take(SHOWN_PER_MODE)
+        .enumerate()
+        .map(|(rank, entry)| format!("  {}. {}  (level {})", rank + 1, entry.score, entry.level))
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimedRun {
+    pub time: Duration,
+    pub score: u32,
+    pub pieces: u32,
+    pub seed: u64,
+}
+
+// Fastest run to each line goal, per mode.
+#[derive(Resource, Debug, Default)]
+pub struct BestTimes(HashMap<(&'static str, u32), TimedRun>);
+
+impl BestTimes {
+    // Returns true when the run beats the goal's previous best.
+    pub fn submit(&mut self, mode: &'static str, goal: u32, run: TimedRun) -> bool {
+        match self.0.get(&(mode, goal)) {
+            Some(best) if best.time <= run.time => false,
+            _ => {
+                self.0.insert((mode, goal), run);
+                true
+            }
+        }
+    }
+
+    pub fn get(&self, mode: &'static str, goal: u32) -> Option<&TimedRun> {
+        self.0.get(&(mode, goal))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, u32, &TimedRun)> {
+        self.0.iter().map(|(&(mode, goal), run)| (mode, goal, run))
+    }
+}
+
+// One line per goal with the mode's best time to it.
+pub fn best_time_table(world: &World, mode: &'static str, goals: &[u32]) -> Vec<String> {
+    let bests = world.get_resource::<BestTimes>();
+    goals
+        .iter()
+        .map(|&goal| {
+            let time = bests
+                .and_then(|bests| bests.get(mode, goal))
+                .map_or("-".to_string(), |best| format_time(best.time.as_secs_f32()));
+            format!("  {goal} lines: {time}")
+        })
+        .collect()
+}
+
+// Where high scores and best times are kept between sessions, under the
+// user's data directory. `None` keeps them for this session only.
+#[derive(Resource, Debug, Clone, PartialEq, Eq)]
+pub struct RecordsFile(pub Option<PathBuf>);
+
+impl Default for RecordsFile {
+    fn default() -> Self {
+        RecordsFile(data_dir().map(|dir| dir.join("tetris_rust_bevy").join("records.txt")))
+    }
+}
+
+fn data_dir() -> Option<PathBuf> {
+    let from_env = |name| std::env::var_os(name).filter(|value| !value.is_empty());
+    from_env("XDG_DATA_HOME")
+        .or_else(|| from_env("APPDATA"))
+        .map(PathBuf::from)
+        .or_else(|| from_env("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+}
+
+// One record per line:
+//   score <mode> <score> <level> <seed>
+//   time <mode> <goal> <millis> <score> <pieces> <seed>
+pub fn write_records(high_scores: &HighScores, bests: &BestTimes) -> String {
+    let mut text = String::new();
+    for (mode, entry) in high_scores.iter() {
+        text += &format!(
+            "score {mode} {} {} {}\n",
+            entry.score, entry.level, entry.seed
+        );
+    }
+    for (mode, goal, run) in bests.iter() {
+        text += &format!(
+            "time {mode} {goal} {} {} {} {}\n",
+            run.time.as_millis(),
+            run.score,
+            run.pieces,
+            run.seed
+        );
+    }
+    text
+}
+
+// Lines for modes that are no longer registered, or that don't parse, are
+// skipped.
+pub fn read_records(
+    text: &str,
+    modes: &GameModes,
+    high_scores: &mut HighScores,
+    bests: &mut BestTimes,
+) {
+    for line in text.lines() {
+        let fields: Vec<_> = line.split_whitespace().collect();
+        let Some(mode) = fields
+            .get(1)
+            .and_then(|id| modes.get(id))
+            .map(|mode| mode.id)
+        else {
+            continue;
+        };
+        let numbers: Option<Vec<u64>> =
+            fields[2..].iter().map(|field| field.parse().ok()).collect();
+        match (fields[0], numbers.as_deref()) {
+            ("score", Some(&[score, level, seed])) => {
+                high_scores.submit(
+                    mode,
+                    ScoreEntry {
+                        score: score as u32,
+                        level: level as u32,
+                        seed,
+                    },
+                );
+            }
+            ("time", Some(&[goal, millis, score, pieces, seed])) => {
+                bests.submit(
+                    mode,
+                    goal as u32,
+                    TimedRun {
+                        time: Duration::from_millis(millis),
+                        score: score as u32,
+                        pieces: pieces as u32,
+                        seed,
+                    },
+                );
+            }
+            _ => {}
+        }
+    }
+}
+
+pub fn load_records(
+    file: Res<RecordsFile>,
+    modes: Res<GameModes>,
+    mut high_scores: ResMut<HighScores>,
+    mut bests: ResMut<BestTimes>,
+) {
+    let Some(path) = &file.0 else {
+        return;
+    };
+    if let Ok(text) = fs::read_to_string(path) {
+        read_records(&text, &modes, &mut high_scores, &mut bests);
+    }
+}
+
+pub fn save_records(file: Res<RecordsFile>, high_scores: Res<HighScores>, bests: Res<BestTimes>) {
+    let Some(path) = &file.0 else {
+        return;
+    };
+    let text = write_records(&high_scores, &bests);
+    let saved = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| fs::write(path, text));
+    if let Err(error) = saved {
+        println!("Could not save records to {}: {error}", path.display());
+    }
+}
+
+// A finished game can be played again from its seed: the same seed deals
+// the same pieces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReplayEntry {
+    pub mode: &'static str,
+    pub seed: u64,
+    pub score: u32,
+}
+
+#[derive(Resource, Debug, Default)]
+pub struct ReplayLog(VecDeque<ReplayEntry>);
+
+impl ReplayLog {
+    pub fn record(&mut self, entry: ReplayEntry) {
+        self.0.push_front(entry);
+        self.0.truncate(MAX_REPLAYS);
+    }
+
+    // Most recent first.
+    pub fn entries(&self) -> impl Iterator<Item = &ReplayEntry> {
+        self.0.iter()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&ReplayEntry> {
+        self.0.get(index)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+pub fn record_finished_game(
+    selected: Res<SelectedMode>,
+    game: Res<CurrentGame>,
+    mut high_scores: ResMut<HighScores>,
+    mut replay_log: ResMut<ReplayLog>,
+) {
+    let entry = ScoreEntry {
+        score: game.score.value,
+        level: game.level.value,
+        seed: game.rng.seed(),
+    };
+    if let Some(rank) = high_scores.submit(selected.0, entry) {
+        println!("New high score! Rank {}", rank + 1);
+    }
+    replay_log.record(ReplayEntry {
+        mode: selected.0,
+        seed: game.rng.seed(),
+        score: game.score.value,
+    });
+}
@@ -1,12 +1,81 @@
 use bevy::prelude::*;
 
-#[derive(Resource, Default)]
-pub struct Score {
-    pub value: u32,
+use crate::game_constants::{SIDE_PANEL_ROWS, SIDE_PANEL_WIDTH, TEXTURE_SIZE};
+use crate::game_core::Game;
+use crate::game_core::board::BoardSize;
+use crate::game_core::randomizer::{PieceRandomizer, RandomizerKind};
+use crate::game_core::tspin::PieceLocked;
+
+// Rows of the window for a field this tall; the side panels sit at the top.
+pub fn window_rows(visible_rows: usize) -> usize {
+    visible_rows.max(SIDE_PANEL_ROWS)
+}
+
+#[derive(Resource, Debug, Default, Copy, Clone, PartialEq, Eq, Deref)]
+pub struct BoardConfig(pub BoardSize);
+
+impl BoardConfig {
+    pub fn new(width: usize, height: usize) -> Self {
+        BoardConfig(BoardSize::new(width, height))
+    }
+
+    // Playfield plus the hold and next panels on either side, in pixels.
+    pub fn window_size(&self) -> Vec2 {
+        Vec2::new(
+            (self.width as u32 * TEXTURE_SIZE + 2 * SIDE_PANEL_WIDTH) as f32,
+            (window_rows(self.height) as u32 * TEXTURE_SIZE) as f32,
+        )
+    }
 }
 
-#[derive(Resource, Default)]
-pub struct Level {
-    pub value: u32,
-    pub lines_cleared_in_level: u32,
+// The game being played. Systems turn input and frame time into calls on
+// it; everything it decides is read back from here.
+#[derive(Resource, Deref, DerefMut)]
+pub struct CurrentGame(pub Game);
+
+impl Default for CurrentGame {
+    fn default() -> Self {
+        CurrentGame(Game::unstarted(
+            rand::random(),
+            &BoardSize::default(),
+            PieceRandomizer::default(),
+        ))
+    }
 }
+
+// Runs `step` on the game but only flags it as changed when the score,
+// level or line count moved, so systems that touch it every frame don't
+// make `resource_changed::<CurrentGame>` fire every frame.
+pub fn step_quietly<R>(game: &mut ResMut<CurrentGame>, step: impl FnOnce(&mut Game) -> R) -> R {
+    let before = (
+        game.score.clone(),
+        game.level.clone(),
+        game.lines_cleared,
+        game.pieces_placed,
+    );
+    let result = step(game.bypass_change_detection());
+    if before
+        != (
+            game.score.clone(),
+            game.level.clone(),
+            game.lines_cleared,
+            game.pieces_placed,
+        )
+    {
+        game.set_changed();
+    }
+    result
+}
+
+// The randomizer each new game deals from, picked in settings or with
+// `--randomizer`. Modes with a fixed queue swap it out for their games.
+#[derive(Resource, Debug, Default, Copy, Clone, PartialEq, Eq, Deref, DerefMut)]
+pub struct RandomizerChoice(pub RandomizerKind);
+
+// Sent for every piece the game locks.
+#[derive(Event, Debug, Copy, Clone, PartialEq, Eq, Deref)]
+pub struct LockEvent(pub PieceLocked);
+
+// Seed for the next game to start; a fresh random one is drawn when empty.
+#[derive(Resource, Debug, Default)]
+pub struct NextSeed(pub Option<u64>);
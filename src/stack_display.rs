@@ -0,0 +1,84 @@
+use bevy::prelude::*;
+use std::time::Duration;
+
+use crate::resources::{CurrentGame, LockEvent};
+
+// How long a fading block takes to go from solid to gone.
+pub const FADE_OUT_SECONDS: f32 = 1.0;
+// How long the whole stack shows after a line clear.
+pub const REVEAL_SECONDS: f32 = 1.0;
+
+// How locked blocks are drawn. Fading blocks stay solid for the given
+// number of seconds after they lock; invisible ones are never shown while
+// the game is running.
+#[derive(Resource, Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum StackDisplay {
+    #[default]
+    Visible,
+    Fading(u32),
+    Invisible,
+}
+
+pub const STACK_DISPLAYS: [StackDisplay; 5] = [
+    StackDisplay::Visible,
+    StackDisplay::Fading(10),
+    StackDisplay::Fading(5),
+    StackDisplay::Fading(2),
+    StackDisplay::Invisible,
+];
+
+impl StackDisplay {
+    pub fn step(&mut self, direction: isize) {
+        let index = STACK_DISPLAYS
+            .iter()
+            .position(|display| display == self)
+            .unwrap_or(0) as isize;
+        let index = (index + direction).clamp(0, STACK_DISPLAYS.len() as isize - 1);
+        *self = STACK_DISPLAYS[index as usize];
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            StackDisplay::Visible => "visible".to_string(),
+            StackDisplay::Fading(seconds) => format!("fades after {seconds}s"),
+            StackDisplay::Invisible => "invisible".to_string(),
+        }
+    }
+
+    // Opacity of a block locked at `locked_at` when the clock reads `now`.
+    pub fn alpha(&self, locked_at: Duration, now: Duration) -> f32 {
+        match *self {
+            StackDisplay::Visible => 1.0,
+            StackDisplay::Fading(seconds) => {
+                let age = now.saturating_sub(locked_at).as_secs_f32();
+                (1.0 - (age - seconds as f32) / FADE_OUT_SECONDS).clamp(0.0, 1.0)
+            }
+            StackDisplay::Invisible => 0.0,
+        }
+    }
+}
+
+// Shows the whole stack until the game's time passes `until`.
+#[derive(Resource, Debug, Default, Clone, PartialEq, Eq)]
+pub struct StackReveal {
+    pub until: Duration,
+}
+
+impl StackReveal {
+    pub fn is_showing(&self, now: Duration) -> bool {
+        now < self.until
+    }
+}
+
+// Flashes the whole stack when lines clear.
+pub fn reveal_stack(
+    game: Res<CurrentGame>,
+    mut locked_events: EventReader<LockEvent>,
+    mut reveal: ResMut<StackReveal>,
+) {
+    for event in locked_events.read() {
+        if event.lines_cleared > 0 {
+            reveal.until = game.time() + Duration::from_secs_f32(REVEAL_SECONDS);
+        }
+    }
+}
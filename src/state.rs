@@ -1,8 +1,21 @@
 use bevy::prelude::*;
 
 #[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, States)]
+#[states(scoped_entities)]
 pub enum GameState {
     #[default]
+    MainMenu,
     Playing,
+    Paused,
     GameOver,
+    // the mode's goal was reached
+    Finished,
+}
+
+// What the results screen shows. Filled in by the mode that ends a game
+// with `GameState::Finished`.
+#[derive(Resource, Debug, Default, Clone)]
+pub struct GameResults {
+    pub headline: String,
+    pub lines: Vec<String>,
 }
@@ -0,0 +1,97 @@
+use bevy::input::ButtonInput;
+use bevy::input::keyboard::KeyCode;
+use bevy::prelude::*;
+use std::time::Duration;
+
+use crate::systems::hold::HOLD_KEYS;
+use crate::systems::input::{
+    HARD_DROP_KEY, MOVE_LEFT_KEY, MOVE_RIGHT_KEY, ROTATE_CCW_KEYS, ROTATE_CW_KEYS, SOFT_DROP_KEY,
+};
+
+pub const GAMEPLAY_KEYS: [KeyCode; 10] = [
+    MOVE_LEFT_KEY,
+    MOVE_RIGHT_KEY,
+    SOFT_DROP_KEY,
+    HARD_DROP_KEY,
+    ROTATE_CW_KEYS[0],
+    ROTATE_CW_KEYS[1],
+    ROTATE_CCW_KEYS[0],
+    ROTATE_CCW_KEYS[1],
+    HOLD_KEYS[0],
+    HOLD_KEYS[1],
+];
+
+#[derive(Resource, Debug, Default, Clone, PartialEq, Eq)]
+pub struct GameStats {
+    pub keys_pressed: u32,
+    pub finesse_faults: u32,
+}
+
+impl GameStats {
+    pub fn keys_per_piece(&self, pieces_placed: u32) -> f32 {
+        if pieces_placed > 0 {
+            self.keys_pressed as f32 / pieces_placed as f32
+        } else {
+            0.0
+        }
+    }
+}
+
+// Time spent in play. It starts with the first key pressed and stops while
+// the game is paused.
+#[derive(Resource, Debug, Default, Clone, PartialEq)]
+pub struct GameClock {
+    pub elapsed: Duration,
+    pub started: bool,
+}
+
+impl GameClock {
+    pub fn seconds(&self) -> f32 {
+        self.elapsed.as_secs_f32()
+    }
+
+    pub fn pieces_per_second(&self, pieces_placed: u32) -> f32 {
+        if self.elapsed > Duration::ZERO {
+            pieces_placed as f32 / self.seconds()
+        } else {
+            0.0
+        }
+    }
+}
+
+// Formats seconds as m:ss.cc.
+pub fn format_time(seconds: f32) -> String {
+    let centis = (seconds.max(0.0) * 100.0) as u32;
+    format!(
+        "{}:{:02}.{:02}",
+        centis / 6000,
+        centis / 100 % 60,
+        centis % 100
+    )
+}
+
+pub fn count_keys(keyboard_input: Res<ButtonInput<KeyCode>>, mut stats: ResMut<GameStats>) {
+    let pressed = keyboard_input
+        .get_just_pressed()
+        .filter(|key| GAMEPLAY_KEYS.contains(key))
+        .count() as u32;
+    if pressed > 0 {
+        stats.keys_pressed += pressed;
+    }
+}
+
+// Only gameplay keys start the clock, so pausing before the first move
+// doesn't count.
+pub fn tick_clock(
+    time: Res<Time>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut clock: ResMut<GameClock>,
+) {
+    if !clock.started {
+        if !keyboard_input.any_just_pressed(GAMEPLAY_KEYS) {
+            return;
+        }
+        clock.started = true;
+    }
+    clock.elapsed += time.delta();
+}
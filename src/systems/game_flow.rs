@@ -0,0 +1,157 @@
+use bevy::ecs::system::SystemParam;
+use bevy::input::ButtonInput;
+use bevy::input::keyboard::KeyCode;
+use bevy::prelude::*;
+
+use crate::auto_repeat::AutoRepeat;
+use crate::finesse::PieceInputs;
+use crate::resources::{BoardConfig, CurrentGame, NextSeed, RandomizerChoice};
+use crate::stack_display::StackReveal;
+use crate::state::{GameResults, GameState};
+use crate::stats::{GameClock, GameStats};
+
+pub const CONFIRM_KEY: KeyCode = KeyCode::Enter;
+pub const PAUSE_KEYS: [KeyCode; 2] = [KeyCode::Escape, KeyCode::KeyP];
+pub const QUIT_KEY: KeyCode = KeyCode::KeyQ;
+
+// Everything that belongs to one game and starts over with the next.
+#[derive(SystemParam)]
+pub struct GameSession<'w> {
+    pub board: Res<'w, BoardConfig>,
+    pub randomizer: Res<'w, RandomizerChoice>,
+    pub game: ResMut<'w, CurrentGame>,
+    pub next_seed: ResMut<'w, NextSeed>,
+    pub auto_repeat: ResMut<'w, AutoRepeat>,
+    pub inputs: ResMut<'w, PieceInputs>,
+    pub results: ResMut<'w, GameResults>,
+    pub stats: ResMut<'w, GameStats>,
+    pub clock: ResMut<'w, GameClock>,
+    pub reveal: ResMut<'w, StackReveal>,
+}
+
+impl GameSession<'_> {
+    pub fn reset(&mut self) {
+        let seed = self.next_seed.0.take().unwrap_or_else(rand::random);
+        self.game.randomizer = self.randomizer.build();
+        self.game.restart(seed, &self.board);
+        *self.auto_repeat = AutoRepeat::default();
+        *self.inputs = PieceInputs::default();
+        *self.results = GameResults::default();
+        *self.stats = GameStats::default();
+        *self.clock = GameClock::default();
+        *self.reveal = StackReveal::default();
+    }
+}
+
+// Runs when a game is left for good: entering the main menu and leaving
+// game over or the results screen.
+pub fn reset_game(mut session: GameSession) {
+    session.reset();
+}
+
+// Runs on entering Playing, after the mode has set up a fresh game;
+// resuming from pause finds it already started.
+pub fn start_game(mut game: ResMut<CurrentGame>) {
+    game.start();
+}
+
+pub fn end_topped_out_game(game: Res<CurrentGame>, mut next_state: ResMut<NextState<GameState>>) {
+    if let Some(reason) = game.top_out() {
+        println!("Game Over! {reason:?}");
+        next_state.set(GameState::GameOver);
+    }
+}
+
+pub fn toggle_pause(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    match state.get() {
+        GameState::Playing if keyboard_input.any_just_pressed(PAUSE_KEYS) => {
+            next_state.set(GameState::Paused);
+        }
+        GameState::Paused if keyboard_input.any_just_pressed(PAUSE_KEYS) => {
+            next_state.set(GameState::Playing);
+        }
+        GameState::Paused if keyboard_input.just_pressed(QUIT_KEY) => {
+            next_state.set(GameState::MainMenu);
+        }
+        _ => {}
+    }
+}
+
+pub fn game_over_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if keyboard_input.just_pressed(CONFIRM_KEY) {
+        next_state.set(GameState::Playing);
+    } else if keyboard_input.just_pressed(KeyCode::Escape) {
+        next_state.set(GameState::MainMenu);
+    }
+}
+
+// A full-window overlay that is despawned when `state` is left.
+pub fn spawn_screen<S: States>(commands: &mut Commands, state: S, lines: &[(String, f32)]) {
+    commands
+        .spawn((
+            StateScoped(state),
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(12.0),
+                ..default()
+            },
+            BackgroundColor(Color::BLACK.with_alpha(0.6)),
+        ))
+        .with_children(|parent| {
+            for (text, size) in lines {
+                parent.spawn((
+                    Text::new(text.clone()),
+                    TextFont {
+                        font_size: *size,
+                        ..default()
+                    },
+                ));
+            }
+        });
+}
+
+pub fn spawn_pause_screen(mut commands: Commands) {
+    spawn_screen(
+        &mut commands,
+        GameState::Paused,
+        &[
+            ("Paused".to_string(), 48.0),
+            ("Esc: resume   Q: main menu".to_string(), 20.0),
+        ],
+    );
+}
+
+// Modes can add their own lines through `GameResults` before this runs.
+pub fn spawn_game_over_screen(
+    mut commands: Commands,
+    game: Res<CurrentGame>,
+    results: Res<GameResults>,
+) {
+    let mut lines = vec![("Game Over".to_string(), 48.0)];
+    if let Some(reason) = game.top_out() {
+        lines.push((reason.label().to_string(), 24.0));
+    }
+    lines.push((format!("Score: {}", game.score.value), 28.0));
+    lines.push((format!("Level: {}", game.level.value), 28.0));
+    lines.extend(results.lines.iter().map(|line| (line.clone(), 24.0)));
+    lines.push(("Enter: play again   Esc: main menu".to_string(), 20.0));
+    spawn_screen(&mut commands, GameState::GameOver, &lines);
+}
+
+pub fn spawn_results_screen(mut commands: Commands, results: Res<GameResults>) {
+    let mut lines = vec![(results.headline.clone(), 48.0)];
+    lines.extend(results.lines.iter().map(|line| (line.clone(), 24.0)));
+    lines.push(("Enter: play again   Esc: main menu".to_string(), 20.0));
+    spawn_screen(&mut commands, GameState::Finished, &lines);
+}
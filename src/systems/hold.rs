@@ -0,0 +1,14 @@
+use bevy::input::ButtonInput;
+use bevy::input::keyboard::KeyCode;
+use bevy::prelude::*;
+
+use crate::game_core::Action;
+use crate::resources::CurrentGame;
+
+pub const HOLD_KEYS: [KeyCode; 2] = [KeyCode::KeyC, KeyCode::ShiftLeft];
+
+pub fn hold_piece(keyboard_input: Res<ButtonInput<KeyCode>>, mut game: ResMut<CurrentGame>) {
+    if keyboard_input.any_just_pressed(HOLD_KEYS) {
+        game.apply(Action::Hold);
+    }
+}
@@ -0,0 +1,138 @@
+use bevy::prelude::*;
+
+use crate::game_core::scoring::Score;
+use crate::resources::CurrentGame;
+use crate::stats::{GameClock, format_time};
+
+#[derive(Component, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum HudField {
+    Score,
+    Level,
+    Lines,
+    Time,
+    Pieces,
+    PiecesPerSecond,
+    Streak,
+    // a one-off message from the mode, cleared between games
+    Banner,
+}
+
+const FIELDS: [HudField; 8] = [
+    HudField::Score,
+    HudField::Level,
+    HudField::Lines,
+    HudField::Time,
+    HudField::Pieces,
+    HudField::PiecesPerSecond,
+    HudField::Streak,
+    HudField::Banner,
+];
+
+#[derive(Component, Debug)]
+pub struct Hud;
+
+// Sits in the bottom of the left panel, under the hold preview. Each field
+// is only rewritten when the resource behind it changes.
+pub fn spawn_hud(mut commands: Commands) {
+    commands
+        .spawn((
+            Hud,
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(12.0),
+                bottom: Val::Px(12.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(4.0),
+                ..default()
+            },
+            Visibility::Hidden,
+        ))
+        .with_children(|parent| {
+            for field in FIELDS {
+                parent.spawn((
+                    field,
+                    Text::default(),
+                    TextFont {
+                        font_size: 18.0,
+                        ..default()
+                    },
+                ));
+            }
+        });
+}
+
+pub fn show_hud(mut hud: Query<&mut Visibility, With<Hud>>) {
+    for mut visibility in hud.iter_mut() {
+        *visibility = Visibility::Inherited;
+    }
+}
+
+pub fn hide_hud(mut hud: Query<&mut Visibility, With<Hud>>) {
+    for mut visibility in hud.iter_mut() {
+        *visibility = Visibility::Hidden;
+    }
+}
+
+pub fn set_field(fields: &mut Query<(&HudField, &mut Text)>, field: HudField, value: String) {
+    for (hud_field, mut text) in fields.iter_mut() {
+        if *hud_field == field && text.0 != value {
+            text.0 = value.clone();
+        }
+    }
+}
+
+pub fn clear_banner(mut fields: Query<(&HudField, &mut Text)>) {
+    set_field(&mut fields, HudField::Banner, String::new());
+}
+
+pub fn streak_label(score: &Score) -> String {
+    let mut parts = Vec::new();
+    if let Some(combo) = score.combo.filter(|&combo| combo > 0) {
+        parts.push(format!("Combo x{combo}"));
+    }
+    if score.back_to_back {
+        parts.push("B2B".to_string());
+    }
+    parts.join("  ")
+}
+
+pub fn update_score_hud(game: Res<CurrentGame>, mut fields: Query<(&HudField, &mut Text)>) {
+    set_field(
+        &mut fields,
+        HudField::Score,
+        format!("Score: {}", game.score.value),
+    );
+    set_field(&mut fields, HudField::Streak, streak_label(&game.score));
+    set_field(
+        &mut fields,
+        HudField::Level,
+        format!("Level: {}", game.level.value),
+    );
+    set_field(
+        &mut fields,
+        HudField::Lines,
+        format!("Lines: {}", game.lines_cleared),
+    );
+    set_field(
+        &mut fields,
+        HudField::Pieces,
+        format!("Pieces: {}", game.pieces_placed),
+    );
+}
+
+pub fn update_clock_hud(
+    clock: Res<GameClock>,
+    game: Res<CurrentGame>,
+    mut fields: Query<(&HudField, &mut Text)>,
+) {
+    set_field(
+        &mut fields,
+        HudField::Time,
+        format!("Time: {}", format_time(clock.seconds())),
+    );
+    set_field(
+        &mut fields,
+        HudField::PiecesPerSecond,
+        format!("PPS: {:.2}", clock.pieces_per_second(game.pieces_placed)),
+    );
+}
@@ -2,143 +2,93 @@ use bevy::input::ButtonInput;
 use bevy::input::keyboard::KeyCode;
 use bevy::prelude::*;
 
-use crate::components::{Piece, Position};
-use crate::game_constants::{NUM_BLOCKS_X, NUM_BLOCKS_Y};
-use crate::game_types::{GameMap, Presence};
-use crate::piece_utils::get_block_matrix;
-use crate::state::GameState;
+use crate::auto_repeat::{AutoRepeat, InputSettings};
+use crate::game_core::{Action, Game};
+use crate::resources::{CurrentGame, LockEvent, step_quietly};
 
-use super::movement::can_move;
-use super::spawning::spawn_piece;
+pub const ROTATE_CW_KEYS: [KeyCode; 2] = [KeyCode::ArrowUp, KeyCode::KeyX];
+pub const ROTATE_CCW_KEYS: [KeyCode; 2] = [KeyCode::ControlLeft, KeyCode::KeyZ];
+pub const HARD_DROP_KEY: KeyCode = KeyCode::Space;
+pub const MOVE_LEFT_KEY: KeyCode = KeyCode::ArrowLeft;
+pub const MOVE_RIGHT_KEY: KeyCode = KeyCode::ArrowRight;
+pub const SOFT_DROP_KEY: KeyCode = KeyCode::ArrowDown;
 
 pub fn handle_input(
-    mut commands: Commands,
     keyboard_input: Res<ButtonInput<KeyCode>>,
-    mut query: Query<(Entity, &mut Position, &mut Piece)>,
-    mut game_map: ResMut<GameMap>,
-    mut score: ResMut<crate::resources::Score>,
-    mut game_state: ResMut<NextState<GameState>>,
+    mut game: ResMut<CurrentGame>,
+    mut locked_events: EventWriter<LockEvent>,
 ) {
-    if let Ok((entity, mut position, mut piece)) = query.single_mut() {
-        if keyboard_input.just_pressed(bevy::input::keyboard::KeyCode::ArrowLeft) {
-            let new_x = position.x - 1;
-            if can_move_horizontally(&piece, &position, new_x, &game_map) {
-                position.x = new_x;
-            }
-        }
-        if keyboard_input.just_pressed(bevy::input::keyboard::KeyCode::ArrowRight) {
-            let new_x = position.x + 1;
-            if can_move_horizontally(&piece, &position, new_x, &game_map) {
-                position.x = new_x;
-            }
-        }
-        if keyboard_input.just_pressed(bevy::input::keyboard::KeyCode::ArrowDown) {
-            let new_y = position.y + 1;
-            if can_move(&piece, &position, new_y, &game_map) {
-                position.y = new_y;
-            }
-        }
-
-        if keyboard_input.just_pressed(bevy::input::keyboard::KeyCode::Space) {
-            println!("Space key pressed");
-            let mut final_y = position.y;
-            while can_move(&piece, &position, final_y + 1, &game_map) {
-                final_y += 1;
-            }
-
-            if final_y > position.y {
-                score.value += (final_y - position.y) as u32;
-                position.y = final_y;
-            }
-
-            let piece_matrix = get_block_matrix(piece.states[piece.current_state], piece.color);
-            for (my, row) in piece_matrix.iter().enumerate() {
-                for (mx, cell) in row.iter().enumerate() {
-                    if let Presence::Yes(color) = *cell {
-                        let map_x = position.x + mx as isize;
-                        let map_y = position.y + my as isize;
-                        if map_x >= 0
-                            && map_x < NUM_BLOCKS_X as isize
-                            && map_y >= 0
-                            && map_y < NUM_BLOCKS_Y as isize
-                        {
-                            game_map.0[map_y as usize][map_x as usize] = Presence::Yes(color);
-                        }
-                    }
-                }
-            }
-            commands.entity(entity).despawn();
-            spawn_piece(&mut commands, &game_map, &mut game_state);
-        }
-
-        if keyboard_input.just_pressed(bevy::input::keyboard::KeyCode::ArrowUp) {
-            let old_state = piece.current_state;
-            let next_state = (piece.current_state + 1) % 4;
-            let mut rotated_piece = *piece;
-            rotated_piece.current_state = next_state;
-
-            if can_rotate(&rotated_piece, &position, &game_map) {
-                piece.current_state = next_state;
-            } else {
-                piece.current_state = old_state;
-            }
-        }
+    if keyboard_input.any_just_pressed(ROTATE_CW_KEYS) {
+        game.apply(Action::RotateClockwise);
+    } else if keyboard_input.any_just_pressed(ROTATE_CCW_KEYS) {
+        game.apply(Action::RotateCounterClockwise);
+    }
+    if keyboard_input.just_pressed(HARD_DROP_KEY)
+        && let Some(locked) = game.apply(Action::HardDrop)
+    {
+        locked_events.write(LockEvent(locked));
     }
 }
 
-pub fn can_rotate(piece: &Piece, current_pos: &Position, game_map: &GameMap) -> bool {
-    let piece_matrix = get_block_matrix(piece.states[piece.current_state], piece.color);
-    for (my, row) in piece_matrix.iter().enumerate() {
-        for (mx, cell) in row.iter().enumerate() {
-            if let Presence::Yes(_) = *cell {
-                let block_x = current_pos.x + mx as isize;
-                let block_y = current_pos.y + my as isize;
-
-                if block_x < 0
-                    || block_x >= NUM_BLOCKS_X as isize
-                    || block_y < 0
-                    || block_y >= NUM_BLOCKS_Y as isize
-                {
-                    return false;
-                }
-
-                if let Presence::Yes(_) = game_map.0[block_y as usize][block_x as usize] {
-                    return false;
-                }
-            }
+// Applies a move up to `steps` times, stopping once the piece is blocked.
+fn repeat(game: &mut Game, action: Action, steps: usize) {
+    for _ in 0..steps {
+        let before = game.active_piece().map(|(_, position)| *position);
+        game.apply(action);
+        if game.active_piece().map(|(_, position)| *position) == before {
+            break;
         }
     }
-    true
 }
 
-pub fn can_move_horizontally(
-    piece: &Piece,
-    current_pos: &Position,
-    new_x: isize,
-    game_map: &GameMap,
-) -> bool {
-    let piece_matrix = get_block_matrix(piece.states[piece.current_state], piece.color);
-    for (my, row) in piece_matrix.iter().enumerate() {
-        for (mx, cell) in row.iter().enumerate() {
-            if let Presence::Yes(_) = *cell {
-                let block_x = new_x + mx as isize;
-                let block_y = current_pos.y + my as isize;
-
-                if block_x < 0 || block_x >= NUM_BLOCKS_X as isize {
-                    return false;
-                }
+pub fn handle_movement(
+    time: Res<Time>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    settings: Res<InputSettings>,
+    mut auto_repeat: ResMut<AutoRepeat>,
+    mut game: ResMut<CurrentGame>,
+) {
+    if game.active_piece().is_none() {
+        return;
+    }
+    auto_repeat.track(game.spawns(), &settings);
 
-                if block_y >= 0
-                    && block_y < NUM_BLOCKS_Y as isize
-                    && block_x >= 0
-                    && block_x < NUM_BLOCKS_X as isize
-                {
-                    if let Presence::Yes(_) = game_map.0[block_y as usize][block_x as usize] {
-                        return false;
-                    }
-                }
-            }
-        }
+    if keyboard_input.any_just_pressed(ROTATE_CW_KEYS)
+        || keyboard_input.any_just_pressed(ROTATE_CCW_KEYS)
+        || keyboard_input.just_pressed(HARD_DROP_KEY)
+    {
+        auto_repeat.cut(&settings);
     }
-    true
+
+    let dt_ms = time.delta_secs() * 1000.0;
+    let just_pressed = if keyboard_input.just_pressed(MOVE_LEFT_KEY) {
+        Some(-1)
+    } else if keyboard_input.just_pressed(MOVE_RIGHT_KEY) {
+        Some(1)
+    } else {
+        None
+    };
+    let steps = auto_repeat.shift(
+        keyboard_input.pressed(MOVE_LEFT_KEY),
+        keyboard_input.pressed(MOVE_RIGHT_KEY),
+        just_pressed,
+        dt_ms,
+        &settings,
+    );
+    let action = if auto_repeat.direction() < 0 {
+        Action::MoveLeft
+    } else {
+        Action::MoveRight
+    };
+    step_quietly(&mut game, |game| repeat(game, action, steps));
+
+    let gravity_ms = game.gravity.seconds_per_row * 1000.0;
+    let steps = auto_repeat.soft_drop(
+        keyboard_input.pressed(SOFT_DROP_KEY),
+        keyboard_input.just_pressed(SOFT_DROP_KEY),
+        dt_ms,
+        gravity_ms,
+        &settings,
+    );
+    step_quietly(&mut game, |game| repeat(game, Action::SoftDrop, steps));
 }
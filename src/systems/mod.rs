@@ -1,7 +1,7 @@
+pub mod game_flow;
+pub mod hold;
+pub mod hud;
 pub mod input;
-pub mod lines;
 pub mod movement;
 pub mod rendering;
 pub mod setup;
-pub mod spawning;
-pub mod time;
@@ -1,67 +1,176 @@
 use bevy::prelude::*;
 
-use crate::components::{Piece, Position};
-use crate::game_constants::{HEIGHT, NUM_BLOCKS_X, NUM_BLOCKS_Y, TEXTURE_SIZE, WIDTH};
-use crate::game_types::{GameMap, Presence};
-use crate::piece_utils::get_block_matrix;
+use crate::game_constants::{PREVIEW_ROWS, SIDE_PANEL_WIDTH, TEXTURE_SIZE};
+use crate::game_core::board::{GameMap, Presence};
+use crate::game_core::color::GameColor;
+use crate::game_core::piece::{Piece, get_block_matrix};
+use crate::resources::{CurrentGame, window_rows};
+use crate::stack_display::{StackDisplay, StackReveal};
+use crate::state::GameState;
+
+impl From<GameColor> for Color {
+    fn from(game_color: GameColor) -> Self {
+        match game_color {
+            GameColor::Red => Color::srgb_u8(255, 0, 0),
+            GameColor::Green => Color::srgb_u8(0, 255, 0),
+            GameColor::Blue => Color::srgb_u8(0, 0, 255),
+            GameColor::Yellow => Color::srgb_u8(255, 255, 0),
+            GameColor::Cyan => Color::srgb_u8(0, 255, 255),
+            GameColor::Orange => Color::srgb_u8(255, 165, 0),
+            GameColor::Purple => Color::srgb_u8(128, 0, 128),
+            GameColor::Gray => Color::srgb_u8(128, 128, 128),
+            GameColor::Pink => Color::srgb_u8(255, 192, 203),
+            GameColor::DarkGray => Color::srgb_u8(64, 64, 64),
+        }
+    }
+}
+
+fn spawn_block(commands: &mut Commands, color: Color, x: f32, y: f32) {
+    commands.spawn((
+        Sprite {
+            color,
+            custom_size: Some(Vec2::new(TEXTURE_SIZE as f32, TEXTURE_SIZE as f32)),
+            ..default()
+        },
+        Transform::from_xyz(x, y, 0.0),
+        Visibility::Visible,
+    ));
+}
+
+// Pixel size of the visible field.
+fn playfield_size(game_map: &GameMap) -> Vec2 {
+    Vec2::new(
+        (game_map.width() as u32 * TEXTURE_SIZE) as f32,
+        (game_map.visible_rows() as u32 * TEXTURE_SIZE) as f32,
+    )
+}
+
+// Rows of the side panels, which start at the top of the window.
+fn panel_rows(game_map: &GameMap) -> usize {
+    window_rows(game_map.visible_rows())
+}
+
+fn panel_top(game_map: &GameMap) -> f32 {
+    (panel_rows(game_map) as u32 * TEXTURE_SIZE) as f32 / 2.0
+}
+
+fn playfield_cell(size: Vec2, x: isize, y: isize) -> (f32, f32) {
+    (
+        (x as f32 * TEXTURE_SIZE as f32) - (size.x / 2.0) + (TEXTURE_SIZE as f32 / 2.0),
+        (size.y / 2.0) - (y as f32 * TEXTURE_SIZE as f32) - (TEXTURE_SIZE as f32 / 2.0),
+    )
+}
+
+fn spawn_preview(commands: &mut Commands, piece: &Piece, color: Color, left: f32, top: f32) {
+    let piece_matrix = get_block_matrix(piece.states[0], piece.color);
+    for (my, row) in piece_matrix.iter().enumerate() {
+        for (mx, cell) in row.iter().enumerate() {
+            if let Presence::Yes(_) = *cell {
+                spawn_block(
+                    commands,
+                    color,
+                    left + ((mx + 1) as f32 * TEXTURE_SIZE as f32) + (TEXTURE_SIZE as f32 / 2.0),
+                    top - ((my + 1) as f32 * TEXTURE_SIZE as f32) - (TEXTURE_SIZE as f32 / 2.0),
+                );
+            }
+        }
+    }
+}
+
+// The board isn't drawn in the main menu, so take down the last frame.
+pub fn clear_board(mut commands: Commands, blocks: Query<Entity, With<Sprite>>) {
+    for entity in blocks.iter() {
+        commands.entity(entity).despawn();
+    }
+}
 
 pub fn draw_blocks(
     mut commands: Commands,
-    game_map: Res<GameMap>,
-    query_piece: Query<(&Piece, &Position)>,
+    game: Res<CurrentGame>,
+    display: Res<StackDisplay>,
+    reveal: Res<StackReveal>,
+    state: Res<State<GameState>>,
     query_existing_blocks: Query<Entity, With<Sprite>>,
 ) {
     for entity in query_existing_blocks.iter() {
         commands.entity(entity).despawn();
     }
 
-    for y in 0..NUM_BLOCKS_Y {
-        for x in 0..NUM_BLOCKS_X {
-            if let Presence::Yes(color) = game_map.0[y][x] {
-                commands.spawn((
-                    Sprite {
-                        color: color.into(),
-                        custom_size: Some(Vec2::new(TEXTURE_SIZE as f32, TEXTURE_SIZE as f32)),
-                        ..default()
-                    },
-                    Transform::from_xyz(
-                        (x as f32 * TEXTURE_SIZE as f32) - (WIDTH as f32 / 2.0)
-                            + (TEXTURE_SIZE as f32 / 2.0),
-                        (HEIGHT as f32 / 2.0)
-                            - (y as f32 * TEXTURE_SIZE as f32)
-                            - (TEXTURE_SIZE as f32 / 2.0),
-                        0.0,
-                    ),
-                    Visibility::Visible,
-                ));
+    let game_map = &game.game_map;
+    // only the visible rows are drawn; the buffer above stays hidden
+    let size = playfield_size(game_map);
+    let skyline = game_map.buffer_rows();
+    // a hidden stack is shown in full once the game is over
+    let revealed = matches!(state.get(), GameState::GameOver | GameState::Finished)
+        || reveal.is_showing(game.time());
+    for y in skyline..game_map.height() {
+        for x in 0..game_map.width() {
+            if let Presence::Yes(color) = game_map.cell(x, y) {
+                let alpha = if revealed {
+                    1.0
+                } else {
+                    display.alpha(game_map.locked_at(x, y), game.time())
+                };
+                if alpha <= 0.0 {
+                    continue;
+                }
+                let (px, py) = playfield_cell(size, x as isize, (y - skyline) as isize);
+                spawn_block(&mut commands, Color::from(color).with_alpha(alpha), px, py);
             }
         }
     }
 
-    if let Ok((piece, position)) = query_piece.single() {
+    if let Some((piece, position)) = game.active_piece() {
         let piece_matrix = get_block_matrix(piece.states[piece.current_state], piece.color);
         for (my, row) in piece_matrix.iter().enumerate() {
             for (mx, cell) in row.iter().enumerate() {
-                if let Presence::Yes(color) = *cell {
-                    commands.spawn((
-                        Sprite {
-                            color: color.into(),
-                            custom_size: Some(Vec2::new(TEXTURE_SIZE as f32, TEXTURE_SIZE as f32)),
-                            ..default()
-                        },
-                        Transform::from_xyz(
-                            ((position.x + mx as isize) as f32 * TEXTURE_SIZE as f32)
-                                - (WIDTH as f32 / 2.0)
-                                + (TEXTURE_SIZE as f32 / 2.0),
-                            (HEIGHT as f32 / 2.0)
-                                - ((position.y + my as isize) as f32 * TEXTURE_SIZE as f32)
-                                - (TEXTURE_SIZE as f32 / 2.0),
-                            0.0,
-                        ),
-                        Visibility::Visible,
-                    ));
+                let y = position.y + my as isize - skyline as isize;
+                if let Presence::Yes(color) = *cell
+                    && y >= 0
+                {
+                    let (px, py) = playfield_cell(size, position.x + mx as isize, y);
+                    spawn_block(&mut commands, color.into(), px, py);
                 }
             }
         }
     }
 }
+
+pub fn draw_hold(mut commands: Commands, game: Res<CurrentGame>) {
+    let Some(piece_type) = game.held_piece() else {
+        return;
+    };
+    let piece = Piece::from(piece_type);
+    let game_map = &game.game_map;
+    let size = playfield_size(game_map);
+    // grey out the held piece until the next lock allows another swap
+    let color = if game.can_hold() {
+        piece.color.into()
+    } else {
+        Color::from(piece.color).with_alpha(0.3)
+    };
+    spawn_preview(
+        &mut commands,
+        &piece,
+        color,
+        -(size.x / 2.0) - SIDE_PANEL_WIDTH as f32,
+        panel_top(game_map),
+    );
+}
+
+pub fn draw_next(mut commands: Commands, game: Res<CurrentGame>) {
+    let game_map = &game.game_map;
+    let size = playfield_size(game_map);
+    let top = panel_top(game_map);
+    let shown = game.next_queue.shown_pieces(panel_rows(game_map));
+    for (i, piece_type) in shown.enumerate() {
+        let piece = Piece::from(piece_type);
+        spawn_preview(
+            &mut commands,
+            &piece,
+            piece.color.into(),
+            size.x / 2.0,
+            top - (i * PREVIEW_ROWS) as f32 * TEXTURE_SIZE as f32,
+        );
+    }
+}
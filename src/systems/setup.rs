@@ -1,5 +1,19 @@
 use bevy::prelude::*;
+use bevy::render::camera::ScalingMode;
 
-pub fn setup_camera(mut commands: Commands) {
-    commands.spawn(Camera2d);
+use crate::resources::BoardConfig;
+
+pub fn setup_camera(mut commands: Commands, board: Res<BoardConfig>) {
+    // keep the whole board and both panels in view whatever the window size
+    let size = board.window_size();
+    commands.spawn((
+        Camera2d,
+        Projection::from(OrthographicProjection {
+            scaling_mode: ScalingMode::AutoMin {
+                min_width: size.x,
+                min_height: size.y,
+            },
+            ..OrthographicProjection::default_2d()
+        }),
+    ));
 }
@@ -0,0 +1,175 @@
+use bevy::input::ButtonInput;
+use bevy::input::keyboard::KeyCode;
+use bevy::prelude::*;
+use std::time::Duration;
+use tetris_rust_bevy_ver0_16::auto_repeat::{AutoRepeat, DasCharge, InputSettings};
+use tetris_rust_bevy_ver0_16::game_core::Game;
+use tetris_rust_bevy_ver0_16::game_core::board::{BoardSize, PieceType};
+use tetris_rust_bevy_ver0_16::game_core::randomizer::PieceRandomizer;
+use tetris_rust_bevy_ver0_16::resources::CurrentGame;
+use tetris_rust_bevy_ver0_16::systems::input::handle_movement;
+
+fn settings(das_ms: f32, arr_ms: f32) -> InputSettings {
+    InputSettings {
+        das_ms,
+        arr_ms,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn tap_moves_once_then_waits_for_das() {
+    let settings = settings(100.0, 20.0);
+    let mut auto_repeat = AutoRepeat::default();
+
+    assert_eq!(auto_repeat.shift(true, false, Some(-1), 16.0, &settings), 1);
+    assert_eq!(auto_repeat.direction(), -1);
+    assert_eq!(auto_repeat.shift(true, false, None, 50.0, &settings), 0);
+    assert_eq!(auto_repeat.shift(true, false, None, 49.0, &settings), 0);
+    // DAS charged: one shift now, then one per ARR interval
+    assert_eq!(auto_repeat.shift(true, false, None, 1.0, &settings), 1);
+    assert_eq!(auto_repeat.shift(true, false, None, 10.0, &settings), 0);
+    assert_eq!(auto_repeat.shift(true, false, None, 30.0, &settings), 2);
+}
+
+#[test]
+fn arr_zero_shifts_to_the_wall() {
+    let settings = settings(100.0, 0.0);
+    let mut auto_repeat = AutoRepeat::default();
+
+    auto_repeat.shift(false, true, Some(1), 16.0, &settings);
+    assert_eq!(
+        auto_repeat.shift(false, true, None, 100.0, &settings),
+        usize::MAX
+    );
+}
+
+#[test]
+fn releasing_the_key_stops_repeat() {
+    let settings = settings(100.0, 20.0);
+    let mut auto_repeat = AutoRepeat::default();
+
+    auto_repeat.shift(true, false, Some(-1), 16.0, &settings);
+    assert_eq!(auto_repeat.shift(false, false, None, 200.0, &settings), 0);
+    assert_eq!(auto_repeat.direction(), 0);
+}
+
+#[test]
+fn falls_back_to_other_held_direction_with_fresh_charge() {
+    let settings = settings(100.0, 20.0);
+    let mut auto_repeat = AutoRepeat::default();
+
+    auto_repeat.shift(true, false, Some(-1), 16.0, &settings);
+    auto_repeat.shift(true, true, Some(1), 16.0, &settings);
+    assert_eq!(auto_repeat.shift(true, false, None, 50.0, &settings), 0);
+    assert_eq!(auto_repeat.direction(), -1);
+    assert_eq!(auto_repeat.shift(true, false, None, 50.0, &settings), 1);
+}
+
+#[test]
+fn das_cut_delays_repeat() {
+    let settings = InputSettings {
+        das_cut_ms: 50.0,
+        ..settings(100.0, 20.0)
+    };
+    let mut auto_repeat = AutoRepeat::default();
+
+    auto_repeat.shift(true, false, Some(-1), 16.0, &settings);
+    assert_eq!(auto_repeat.shift(true, false, None, 100.0, &settings), 1);
+    auto_repeat.cut(&settings);
+    assert_eq!(auto_repeat.shift(true, false, None, 40.0, &settings), 0);
+    assert_eq!(auto_repeat.shift(true, false, None, 10.0, &settings), 0);
+    assert_eq!(auto_repeat.shift(true, false, None, 20.0, &settings), 1);
+}
+
+#[test]
+fn das_cut_runs_out_while_no_direction_is_held() {
+    let settings = InputSettings {
+        das_cut_ms: 50.0,
+        ..settings(100.0, 20.0)
+    };
+    let mut auto_repeat = AutoRepeat::default();
+
+    auto_repeat.cut(&settings);
+    assert_eq!(auto_repeat.shift(false, false, None, 60.0, &settings), 0);
+    assert_eq!(auto_repeat.shift(true, false, Some(-1), 16.0, &settings), 1);
+    assert_eq!(auto_repeat.shift(true, false, None, 100.0, &settings), 1);
+}
+
+#[test]
+fn das_charge_behaviour_on_spawn() {
+    for (das_charge, expected) in [(DasCharge::Preserve, 1), (DasCharge::ResetOnSpawn, 0)] {
+        let settings = InputSettings {
+            das_charge,
+            ..settings(100.0, 20.0)
+        };
+        let mut auto_repeat = AutoRepeat::default();
+        auto_repeat.track(1, &settings);
+        auto_repeat.shift(true, false, Some(-1), 16.0, &settings);
+        auto_repeat.shift(true, false, None, 100.0, &settings);
+
+        auto_repeat.track(2, &settings);
+        assert_eq!(
+            auto_repeat.shift(true, false, None, 20.0, &settings),
+            expected
+        );
+    }
+}
+
+#[test]
+fn soft_drop_repeats_at_gravity_times_factor() {
+    let settings = InputSettings {
+        soft_drop_factor: 10.0,
+        ..Default::default()
+    };
+    let mut auto_repeat = AutoRepeat::default();
+
+    assert_eq!(
+        auto_repeat.soft_drop(true, true, 16.0, 1000.0, &settings),
+        1
+    );
+    assert_eq!(
+        auto_repeat.soft_drop(true, false, 250.0, 1000.0, &settings),
+        2
+    );
+    assert_eq!(
+        auto_repeat.soft_drop(false, false, 250.0, 1000.0, &settings),
+        0
+    );
+}
+
+fn piece_x(app: &App) -> isize {
+    let game = app.world().resource::<CurrentGame>();
+    game.active_piece().unwrap().1.x
+}
+
+#[test]
+fn holding_left_with_arr_zero_reaches_the_wall() {
+    let mut game = Game::unstarted(1, &BoardSize::default(), PieceRandomizer::default());
+    game.start();
+    game.spawn(PieceType::T);
+
+    let mut app = App::new();
+    app.insert_resource(CurrentGame(game));
+    app.init_resource::<Time>();
+    app.insert_resource(settings(100.0, 0.0));
+    app.init_resource::<AutoRepeat>();
+    app.insert_resource(ButtonInput::<KeyCode>::default());
+    app.add_systems(Update, handle_movement);
+    assert_eq!(piece_x(&app), 3);
+
+    app.world_mut()
+        .resource_mut::<ButtonInput<KeyCode>>()
+        .press(KeyCode::ArrowLeft);
+    app.update();
+    assert_eq!(piece_x(&app), 2);
+
+    app.world_mut()
+        .resource_mut::<ButtonInput<KeyCode>>()
+        .clear();
+    app.world_mut()
+        .resource_mut::<Time>()
+        .advance_by(Duration::from_millis(150));
+    app.update();
+    assert_eq!(piece_x(&app), 0);
+}
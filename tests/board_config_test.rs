@@ -0,0 +1,88 @@
+use tetris_rust_bevy_ver0_16::game_constants::{PREVIEW_ROWS, SIDE_PANEL_ROWS, TEXTURE_SIZE};
+use tetris_rust_bevy_ver0_16::game_core::board::PieceType;
+use tetris_rust_bevy_ver0_16::game_core::piece::Piece;
+use tetris_rust_bevy_ver0_16::game_core::randomizer::{
+    GameRng, MAX_PREVIEWS, NextQueue, PieceRandomizer,
+};
+use tetris_rust_bevy_ver0_16::game_core::{Action, Game, spawn_position};
+use tetris_rust_bevy_ver0_16::resources::{BoardConfig, window_rows};
+
+#[test]
+fn default_board_is_ten_by_twenty() {
+    let game_map = BoardConfig::default().game_map();
+    assert_eq!(game_map.width(), 10);
+    assert_eq!(game_map.visible_rows(), 20);
+    assert_eq!(game_map.height(), 40);
+}
+
+#[test]
+fn board_size_is_clamped_to_what_the_map_supports() {
+    assert_eq!(BoardConfig::new(2, 1), BoardConfig::new(4, 4));
+    assert_eq!(BoardConfig::new(64, 40).width, 32);
+}
+
+#[test]
+fn window_grows_with_the_board() {
+    let small = BoardConfig::default().window_size();
+    let big = BoardConfig::new(20, 40).window_size();
+    assert_eq!(big.y, small.y * 2.0);
+    assert!(big.x > small.x);
+}
+
+#[test]
+fn every_piece_spawns_inside_a_four_wide_board() {
+    let game_map = BoardConfig::new(4, 20).game_map();
+    for piece_type in PieceType::ALL {
+        let piece = Piece::from(piece_type);
+        let position = spawn_position(&game_map, &piece);
+        assert!(
+            !game_map.collides(&piece, piece.current_state, position.x, position.y),
+            "{piece_type:?} spawned in a wall"
+        );
+    }
+}
+
+#[test]
+fn spawn_column_is_centred_on_wide_boards() {
+    let t = Piece::from(PieceType::T);
+    let standard = spawn_position(&BoardConfig::default().game_map(), &t);
+    let big = spawn_position(&BoardConfig::new(20, 40).game_map(), &t);
+    assert_eq!(standard.x, 3);
+    assert_eq!(big.x, 8);
+}
+
+#[test]
+fn games_run_on_custom_boards() {
+    for board in [BoardConfig::new(4, 20), BoardConfig::new(20, 40)] {
+        let mut game = Game::with_board(3, &board, PieceRandomizer::default());
+        assert_eq!(game.game_map.width(), board.width);
+        for _ in 0..10 {
+            game.apply(Action::HardDrop);
+        }
+        assert!(game.pieces_placed > 0);
+    }
+}
+
+#[test]
+fn side_panels_fit_the_window_of_a_four_row_board() {
+    let board = BoardConfig::new(10, 4);
+    let window = board.window_size();
+    assert_eq!(window.y, (SIDE_PANEL_ROWS as u32 * TEXTURE_SIZE) as f32);
+
+    let rows = window_rows(board.height);
+    let mut next_queue = NextQueue::new(MAX_PREVIEWS);
+    next_queue.fill(&mut PieceRandomizer::default(), &mut GameRng::from_seed(0));
+    let shown = next_queue.shown_pieces(rows).count();
+    assert!(shown > 0);
+    assert!(((shown * PREVIEW_ROWS) as u32 * TEXTURE_SIZE) as f32 <= window.y);
+
+    // full height boards keep every preview and the window they had
+    let standard = BoardConfig::default();
+    assert_eq!(standard.window_size().y, (20 * TEXTURE_SIZE) as f32);
+    assert_eq!(
+        next_queue
+            .shown_pieces(window_rows(standard.height))
+            .count(),
+        MAX_PREVIEWS
+    );
+}
@@ -0,0 +1,267 @@
+use bevy::prelude::*;
+use bevy::state::app::StatesPlugin;
+use tetris_rust_bevy_ver0_16::game_constants::MATRIX_ROWS;
+use tetris_rust_bevy_ver0_16::game_core::board::{BoardSize, GameMap, Presence};
+use tetris_rust_bevy_ver0_16::game_core::color::GameColor;
+use tetris_rust_bevy_ver0_16::game_core::randomizer::PieceRandomizer;
+use tetris_rust_bevy_ver0_16::game_core::{Action, Game, TopOut};
+use tetris_rust_bevy_ver0_16::modes::SelectedMode;
+use tetris_rust_bevy_ver0_16::modes::dig::{
+    DIG, DigModePlugin, DigSettings, DigState, GARBAGE_COLOR, GARBAGE_HEIGHT,
+};
+use tetris_rust_bevy_ver0_16::resources::CurrentGame;
+use tetris_rust_bevy_ver0_16::state::{GameResults, GameState};
+use tetris_rust_bevy_ver0_16::stats::{GameClock, GameStats};
+use tetris_rust_bevy_ver0_16::systems::game_flow::{end_topped_out_game, start_game};
+
+const BOTTOM: usize = MATRIX_ROWS - 1;
+
+fn unstarted(seed: u64, board: &BoardSize) -> Game {
+    Game::unstarted(seed, board, PieceRandomizer::default())
+}
+#[test]
+fn inserted_rows_push_the_stack_up() {
+    let mut game_map = GameMap::default();
+    game_map.set(3, BOTTOM, Presence::Yes(GameColor::Blue));
+    let rows = [game_map.row_with_hole(0), game_map.row_with_hole(9)];
+    assert!(game_map.insert_bottom_rows(&rows, GARBAGE_COLOR));
+
+    assert_eq!(game_map.height(), MATRIX_ROWS);
+    assert_eq!(game_map.row_mask(BOTTOM), 0b01_1111_1111);
+    assert_eq!(game_map.row_mask(BOTTOM - 1), 0b11_1111_1110);
+    assert_eq!(game_map.cell(3, BOTTOM - 2), Presence::Yes(GameColor::Blue));
+    assert_eq!(game_map.cell(5, BOTTOM), Presence::Yes(GARBAGE_COLOR));
+}
+
+#[test]
+fn pushing_cells_off_the_top_is_reported() {
+    let mut game_map = GameMap::default();
+    game_map.set(0, 1, Presence::Yes(GameColor::Red));
+    let row = game_map.row_with_hole(4);
+    assert!(game_map.insert_bottom_rows(&[row], GARBAGE_COLOR));
+    assert!(!game_map.insert_bottom_rows(&[row], GARBAGE_COLOR));
+}
+
+#[test]
+fn tidy_garbage_keeps_its_hole_in_one_column() {
+    let settings = DigSettings {
+        lines: 8,
+        messiness: 0,
+    };
+    let mut game = unstarted(3, &BoardSize::default());
+    let mut dig = DigState::new(&settings);
+    dig.refill(&mut game, settings.messiness);
+
+    assert_eq!(game.garbage_rows, 8);
+    assert_eq!(dig.queued, 0);
+    let hole = game.game_map.row_mask(BOTTOM);
+    for y in BOTTOM - 7..=BOTTOM {
+        assert_eq!(game.game_map.row_mask(y), hole);
+        assert_eq!(game.game_map.row_mask(y).count_ones(), 9);
+    }
+    assert_eq!(game.game_map.row_mask(BOTTOM - 8), 0);
+}
+
+#[test]
+fn garbage_is_capped_and_refilled_as_it_is_dug() {
+    let settings = DigSettings {
+        lines: 18,
+        messiness: 100,
+    };
+    let mut game = unstarted(9, &BoardSize::default());
+    let mut dig = DigState::new(&settings);
+    dig.refill(&mut game, settings.messiness);
+    assert_eq!(game.garbage_rows, GARBAGE_HEIGHT);
+    assert_eq!(dig.queued, 8);
+
+    // fill the top garbage row's hole and a player row above it
+    let top = BOTTOM + 1 - GARBAGE_HEIGHT;
+    for x in 0..10 {
+        game.game_map.set(x, top, Presence::Yes(GameColor::Cyan));
+        game.game_map
+            .set(x, top - 1, Presence::Yes(GameColor::Cyan));
+    }
+    assert_eq!(game.clear_full_rows(), 2);
+    assert_eq!(game.garbage_cleared, 1);
+    assert_eq!(game.garbage_rows, GARBAGE_HEIGHT - 1);
+
+    assert!(dig.needs_garbage(&game));
+    dig.refill(&mut game, settings.messiness);
+    assert_eq!(game.garbage_rows, GARBAGE_HEIGHT);
+    assert_eq!(dig.queued, 7);
+}
+
+#[test]
+fn refills_carry_on_the_hole_pattern_under_the_old_garbage() {
+    let settings = DigSettings {
+        lines: 100,
+        messiness: 20,
+    };
+    let mut game = unstarted(5, &BoardSize::default());
+    let mut dig = DigState::new(&settings);
+    dig.refill(&mut game, settings.messiness);
+
+    // most rows keep the hole of the row above, so the first refilled row
+    // should usually line up with the old bottom row
+    let mut lined_up = 0;
+    for _ in 0..18 {
+        let top = BOTTOM + 1 - GARBAGE_HEIGHT;
+        for y in top..top + 5 {
+            for x in 0..10 {
+                game.game_map.set(x, y, Presence::Yes(GameColor::Cyan));
+            }
+        }
+        assert_eq!(game.clear_full_rows(), 5);
+        dig.refill(&mut game, settings.messiness);
+        if game.game_map.row_mask(BOTTOM - 5) == game.game_map.row_mask(BOTTOM - 4) {
+            lined_up += 1;
+        }
+    }
+    assert_eq!(dig.queued, 0);
+    assert!(lined_up >= 12, "only {lined_up} of 18 refills lined up");
+}
+
+#[test]
+fn small_boards_get_less_garbage() {
+    let mut game = unstarted(1, &BoardSize::new(10, 8));
+    let mut dig = DigState::new(&DigSettings::default());
+    dig.refill(&mut game, 50);
+    assert_eq!(game.garbage_rows, 4);
+}
+
+#[test]
+fn settings_step_within_range() {
+    let mut settings = DigSettings::default();
+    settings.step_lines(1);
+    assert_eq!(settings.lines, 100);
+    settings.step_lines(-5);
+    assert_eq!(settings.lines, 10);
+    settings.step_messiness(1);
+    assert_eq!(settings.messiness, 100);
+    settings.step_messiness(-3);
+    assert_eq!(settings.messiness, 70);
+}
+
+fn dig_app(settings: DigSettings) -> App {
+    let mut app = App::new();
+    app.add_plugins(StatesPlugin);
+    app.insert_resource(CurrentGame(unstarted(4, &BoardSize::default())));
+    app.init_resource::<GameStats>();
+    app.init_resource::<GameClock>();
+    app.init_state::<GameState>();
+    app.add_plugins(DigModePlugin);
+    app.insert_resource(settings);
+    app.insert_resource(SelectedMode(DIG.id));
+    app.add_systems(OnEnter(GameState::Playing), start_game);
+    app.add_systems(
+        Update,
+        end_topped_out_game.run_if(in_state(GameState::Playing)),
+    );
+    app.world_mut()
+        .resource_mut::<NextState<GameState>>()
+        .set(GameState::Playing);
+    app.update();
+    app
+}
+
+// Fills in the holes and clears the rows, as a lock there would.
+fn fill_holes(app: &mut App, rows: usize) {
+    let mut game = app.world_mut().resource_mut::<CurrentGame>();
+    for y in BOTTOM + 1 - rows..=BOTTOM {
+        for x in 0..10 {
+            if !game.game_map.is_occupied(x, y) {
+                game.game_map.set(x, y, Presence::Yes(GameColor::Cyan));
+            }
+        }
+    }
+    game.clear_full_rows();
+}
+
+// Soft drops the falling piece onto the garbage and returns its row.
+fn rest_on_stack(app: &mut App) -> isize {
+    let mut game = app.world_mut().resource_mut::<CurrentGame>();
+    for _ in 0..MATRIX_ROWS {
+        game.apply(Action::SoftDrop);
+    }
+    game.active_piece().unwrap().1.y
+}
+
+fn state(app: &App) -> GameState {
+    *app.world().resource::<State<GameState>>().get()
+}
+
+#[test]
+fn a_new_dig_game_starts_with_garbage() {
+    let app = dig_app(DigSettings::default());
+    let game_map = &app.world().resource::<CurrentGame>().game_map;
+    assert_eq!(game_map.row_mask(BOTTOM).count_ones(), 9);
+    assert_eq!(
+        game_map.row_mask(BOTTOM + 1 - GARBAGE_HEIGHT).count_ones(),
+        9
+    );
+    assert_eq!(game_map.row_mask(BOTTOM - GARBAGE_HEIGHT), 0);
+}
+
+#[test]
+fn digging_out_every_garbage_line_finishes_the_game() {
+    let mut app = dig_app(DigSettings {
+        lines: 10,
+        messiness: 50,
+    });
+    fill_holes(&mut app, 4);
+    app.update();
+    assert_eq!(app.world().resource::<CurrentGame>().garbage_cleared, 4);
+    assert_eq!(state(&app), GameState::Playing);
+
+    fill_holes(&mut app, 6);
+    app.update();
+    app.update();
+    assert_eq!(state(&app), GameState::Finished);
+    assert_eq!(
+        app.world().resource::<GameResults>().headline,
+        "10 Garbage Lines Dug"
+    );
+    assert_eq!(
+        app.world()
+            .resource::<CurrentGame>()
+            .game_map
+            .row_mask(BOTTOM),
+        0
+    );
+}
+
+#[test]
+fn rising_garbage_lifts_the_falling_piece() {
+    let mut app = dig_app(DigSettings::default());
+    let start = rest_on_stack(&mut app);
+    app.world_mut().resource_mut::<CurrentGame>().garbage_rows -= 1;
+    app.update();
+
+    assert_eq!(state(&app), GameState::Playing);
+    let game = app.world().resource::<CurrentGame>();
+    assert_eq!(game.active_piece().unwrap().1.y, start - 1);
+}
+
+#[test]
+fn a_piece_with_no_room_above_tops_out() {
+    let mut app = dig_app(DigSettings {
+        lines: 100,
+        messiness: 0,
+    });
+    rest_on_stack(&mut app);
+    // keep the garbage rising under the piece until it reaches the top
+    for _ in 0..MATRIX_ROWS {
+        if state(&app) != GameState::Playing {
+            break;
+        }
+        app.world_mut().resource_mut::<CurrentGame>().garbage_rows -= 1;
+        app.update();
+    }
+    app.update();
+
+    assert_eq!(state(&app), GameState::GameOver);
+    assert_eq!(
+        app.world().resource::<CurrentGame>().top_out(),
+        Some(TopOut::BlockOut)
+    );
+}
@@ -0,0 +1,254 @@
+use std::time::Duration;
+use tetris_rust_bevy_ver0_16::game_core::board::{BoardSize, GameMap, PieceType, Presence};
+use tetris_rust_bevy_ver0_16::game_core::color::GameColor;
+use tetris_rust_bevy_ver0_16::game_core::entry_delay::EntryDelay;
+use tetris_rust_bevy_ver0_16::game_core::piece::{Piece, Position};
+use tetris_rust_bevy_ver0_16::game_core::randomizer::{NextQueue, PieceRandomizer};
+use tetris_rust_bevy_ver0_16::game_core::tspin::PieceLocked;
+use tetris_rust_bevy_ver0_16::game_core::{Action, Game, TopOut, lock_out, spawn_position};
+
+fn snapshot(game: &Game) -> Vec<u32> {
+    (0..game.game_map.height())
+        .map(|y| game.game_map.row_mask(y))
+        .collect()
+}
+
+#[test]
+fn new_game_spawns_active_piece() {
+    let game = Game::new(7);
+    let (piece, position) = game.active_piece().unwrap();
+    assert_eq!(*position, spawn_position(&game.game_map, piece));
+    assert!(!game.is_game_over());
+}
+
+#[test]
+fn moves_and_soft_drop_step_the_piece() {
+    let mut game = Game::new(7);
+    let start = *game.active_piece().unwrap().1;
+
+    assert!(game.apply(Action::MoveLeft).is_none());
+    assert!(game.apply(Action::SoftDrop).is_none());
+    let position = *game.active_piece().unwrap().1;
+    assert_eq!(position.x, start.x - 1);
+    assert_eq!(position.y, start.y + 1);
+    assert_eq!(game.score.value, 1);
+}
+
+#[test]
+fn hard_drop_locks_and_spawns_next_piece() {
+    let mut game = Game::new(3);
+    let locked = game.apply(Action::HardDrop).expect("hard drop should lock");
+    assert_eq!(locked.lines_cleared, 0);
+    assert_eq!(game.pieces_placed, 1);
+    assert!(game.score.value > 0);
+    assert!(snapshot(&game).iter().any(|&mask| mask != 0));
+    let (piece, position) = game.active_piece().unwrap();
+    assert_eq!(*position, spawn_position(&game.game_map, piece));
+}
+
+#[test]
+fn hold_is_allowed_once_per_piece() {
+    let mut game = Game::new(11);
+    let first = game.active_piece().unwrap().0.piece_type;
+
+    game.apply(Action::Hold);
+    assert_eq!(game.held_piece(), Some(first));
+    assert!(!game.can_hold());
+
+    let current = game.active_piece().unwrap().0.piece_type;
+    game.apply(Action::Hold);
+    assert_eq!(game.active_piece().unwrap().0.piece_type, current);
+
+    game.apply(Action::HardDrop);
+    assert!(game.can_hold());
+}
+
+#[test]
+fn gravity_tick_locks_after_lock_delay() {
+    let mut game = Game::new(5);
+    let mut locked = None;
+    for _ in 0..10_000 {
+        if let Some(event) = game.tick(1.0 / 60.0) {
+            locked = Some(event);
+            break;
+        }
+    }
+    assert!(locked.is_some());
+    assert_eq!(game.pieces_placed, 1);
+}
+
+#[test]
+fn same_seed_and_inputs_replay_identically() {
+    let actions = [
+        Action::MoveLeft,
+        Action::RotateClockwise,
+        Action::HardDrop,
+        Action::MoveRight,
+        Action::MoveRight,
+        Action::HardDrop,
+        Action::Hold,
+        Action::RotateCounterClockwise,
+        Action::HardDrop,
+    ];
+    let play = |seed| {
+        let mut game = Game::new(seed);
+        for action in actions {
+            game.apply(action);
+            game.tick(0.1);
+        }
+        game
+    };
+
+    let a = play(42);
+    let b = play(42);
+    assert_eq!(snapshot(&a), snapshot(&b));
+    assert_eq!(a.score.value, b.score.value);
+    assert_eq!(a.held_piece(), b.held_piece());
+}
+
+#[test]
+fn many_games_run_to_completion_without_an_app() {
+    for seed in 0..50 {
+        let mut game = Game::new(seed);
+        let mut steps = 0;
+        while !game.is_game_over() && steps < 1_000 {
+            game.apply(Action::HardDrop);
+            steps += 1;
+        }
+        assert!(game.is_game_over(), "seed {seed} never topped out");
+        assert!(game.active_piece().is_none());
+        assert!(game.apply(Action::HardDrop).is_none());
+    }
+}
+
+#[test]
+fn pieces_spawn_just_above_the_visible_field() {
+    let game = Game::new(1);
+    let position = *game.active_piece().unwrap().1;
+    assert_eq!(game.game_map.height(), 40);
+    assert_eq!(game.game_map.visible_rows(), 20);
+    assert_eq!(position.y, 18);
+}
+
+#[test]
+fn lock_out_reasons_depend_on_cells_above_the_skyline() {
+    let game_map = GameMap::default();
+    let skyline = game_map.buffer_rows() as isize;
+    let t = Piece::from(PieceType::T);
+
+    let below = Position { x: 3, y: skyline };
+    let straddling = Position {
+        x: 3,
+        y: skyline - 1,
+    };
+    let above = Position {
+        x: 3,
+        y: skyline - 2,
+    };
+    assert_eq!(lock_out(&game_map, &t, &below), None);
+    assert_eq!(
+        lock_out(&game_map, &t, &straddling),
+        Some(TopOut::PartialLockOut)
+    );
+    assert_eq!(lock_out(&game_map, &t, &above), Some(TopOut::LockOut));
+}
+
+#[test]
+fn stacking_to_the_top_ends_with_a_top_out_reason() {
+    let mut game = Game::new(9);
+    while !game.is_game_over() {
+        game.apply(Action::HardDrop);
+    }
+    assert!(matches!(
+        game.top_out(),
+        Some(TopOut::BlockOut | TopOut::LockOut | TopOut::PartialLockOut)
+    ));
+}
+
+#[test]
+fn blocked_spawn_is_a_block_out() {
+    let mut game = Game::new(4);
+    for _ in 0..4 {
+        game.apply(Action::MoveLeft);
+    }
+    // a column through the spawn rows, clear of the piece being dropped
+    let skyline = game.game_map.buffer_rows();
+    for y in skyline - 2..game.game_map.height() {
+        game.game_map.set(5, y, Presence::Yes(GameColor::Gray));
+    }
+    game.apply(Action::HardDrop);
+    assert_eq!(game.top_out(), Some(TopOut::BlockOut));
+}
+
+// Fills the bottom four rows but for the right column and drops an I in.
+fn tetris(game: &mut Game) -> PieceLocked {
+    let width = game.game_map.width();
+    let height = game.game_map.height();
+    for y in height - 4..height {
+        for x in 0..width - 1 {
+            game.game_map.set(x, y, Presence::Yes(GameColor::Gray));
+        }
+    }
+    game.spawn(PieceType::I);
+    game.apply(Action::RotateClockwise);
+    for _ in 0..width {
+        game.apply(Action::MoveRight);
+    }
+    game.apply(Action::HardDrop).expect("hard drop should lock")
+}
+
+#[test]
+fn cleared_rows_and_the_next_piece_wait_out_the_entry_delay() {
+    let mut game = Game::new(6);
+    game.entry_delay = EntryDelay {
+        are: 0.1,
+        line_clear: 0.2,
+    };
+    let bottom = game.game_map.height() - 1;
+    assert_eq!(tetris(&mut game).lines_cleared, 4);
+    assert!(game.active_piece().is_none());
+    assert!(game.game_map.is_row_full(bottom));
+
+    game.tick(0.15);
+    assert!(game.game_map.is_row_full(bottom));
+    game.tick(0.1);
+    assert_eq!(game.game_map.row_mask(bottom), 0);
+    assert!(game.active_piece().is_none());
+
+    game.tick(0.1);
+    assert!(game.active_piece().is_some());
+    assert_eq!(game.spawns(), 3);
+}
+
+#[test]
+fn locks_are_stamped_with_the_game_time() {
+    let mut game = Game::unstarted(2, &BoardSize::default(), PieceRandomizer::default());
+    game.tick(4.0);
+    assert_eq!(game.time(), Duration::ZERO);
+
+    game.start();
+    game.tick(4.0);
+    game.spawn(PieceType::O);
+    game.apply(Action::HardDrop);
+    let bottom = game.game_map.height() - 1;
+    assert_eq!(game.game_map.locked_at(4, bottom), game.time());
+    assert_eq!(game.game_map.locked_at(3, bottom), Duration::ZERO);
+}
+
+#[test]
+fn restarting_keeps_the_preview_count_and_waits_for_start() {
+    let mut game = Game::new(8);
+    game.next_queue = NextQueue::new(2);
+    game.apply(Action::HardDrop);
+    game.restart(8, &BoardSize::default());
+
+    assert!(!game.is_started());
+    assert!(game.active_piece().is_none());
+    assert_eq!(game.pieces_placed, 0);
+    assert_eq!(game.next_queue.preview_count(), 2);
+    game.start();
+    assert_eq!(
+        game.active_piece().unwrap().0.piece_type,
+        Game::new(8).active_piece().unwrap().0.piece_type
+    );
+}
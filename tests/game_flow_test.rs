@@ -0,0 +1,184 @@
+use bevy::input::ButtonInput;
+use bevy::input::keyboard::KeyCode;
+use bevy::prelude::*;
+use bevy::state::app::StatesPlugin;
+use tetris_rust_bevy_ver0_16::auto_repeat::AutoRepeat;
+use tetris_rust_bevy_ver0_16::finesse::PieceInputs;
+use tetris_rust_bevy_ver0_16::game_core::Action;
+use tetris_rust_bevy_ver0_16::game_core::board::Presence;
+use tetris_rust_bevy_ver0_16::game_core::color::GameColor;
+use tetris_rust_bevy_ver0_16::resources::{BoardConfig, CurrentGame, NextSeed, RandomizerChoice};
+use tetris_rust_bevy_ver0_16::stack_display::StackReveal;
+use tetris_rust_bevy_ver0_16::state::{GameResults, GameState};
+use tetris_rust_bevy_ver0_16::stats::{GameClock, GameStats};
+use tetris_rust_bevy_ver0_16::systems::game_flow::{
+    end_topped_out_game, game_over_input, reset_game, spawn_game_over_screen, spawn_pause_screen,
+    start_game, toggle_pause,
+};
+
+fn flow_app() -> App {
+    let mut app = App::new();
+    app.add_plugins(StatesPlugin);
+    app.init_resource::<BoardConfig>();
+    app.init_resource::<CurrentGame>();
+    app.init_resource::<NextSeed>();
+    app.init_resource::<RandomizerChoice>();
+    app.init_resource::<AutoRepeat>();
+    app.init_resource::<PieceInputs>();
+    app.init_resource::<GameResults>();
+    app.init_resource::<GameStats>();
+    app.init_resource::<GameClock>();
+    app.init_resource::<StackReveal>();
+    app.insert_resource(ButtonInput::<KeyCode>::default());
+    app.init_state::<GameState>();
+    app.add_systems(OnEnter(GameState::MainMenu), reset_game);
+    app.add_systems(OnExit(GameState::GameOver), reset_game);
+    app.add_systems(OnEnter(GameState::Playing), start_game);
+    app.add_systems(OnEnter(GameState::Paused), spawn_pause_screen);
+    app.add_systems(OnEnter(GameState::GameOver), spawn_game_over_screen);
+    app.add_systems(
+        Update,
+        (
+            toggle_pause.run_if(in_state(GameState::Playing).or(in_state(GameState::Paused))),
+            end_topped_out_game.run_if(in_state(GameState::Playing)),
+            game_over_input.run_if(in_state(GameState::GameOver)),
+        ),
+    );
+    app.update();
+    app
+}
+
+// One update to read the key, one more for the state transition to apply.
+fn press(app: &mut App, key: KeyCode) {
+    {
+        let mut input = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+        input.release_all();
+        input.clear();
+        input.press(key);
+    }
+    app.update();
+    app.world_mut()
+        .resource_mut::<ButtonInput<KeyCode>>()
+        .clear();
+    app.update();
+}
+
+fn start(app: &mut App) {
+    app.world_mut()
+        .resource_mut::<NextState<GameState>>()
+        .set(GameState::Playing);
+    app.update();
+}
+
+fn state(app: &App) -> GameState {
+    *app.world().resource::<State<GameState>>().get()
+}
+
+fn has_piece(app: &App) -> bool {
+    app.world()
+        .resource::<CurrentGame>()
+        .active_piece()
+        .is_some()
+}
+
+fn screen_texts(app: &mut App) -> Vec<String> {
+    let mut query = app.world_mut().query::<&Text>();
+    query.iter(app.world()).map(|text| text.0.clone()).collect()
+}
+
+#[test]
+fn starts_in_main_menu_and_a_game_spawns_its_first_piece() {
+    let mut app = flow_app();
+    assert_eq!(state(&app), GameState::MainMenu);
+    assert!(!has_piece(&app));
+
+    start(&mut app);
+    assert_eq!(state(&app), GameState::Playing);
+    assert!(has_piece(&app));
+}
+
+#[test]
+fn queued_seed_is_used_for_the_next_game() {
+    let mut app = flow_app();
+    app.world_mut().resource_mut::<NextSeed>().0 = Some(77);
+    start(&mut app);
+    press(&mut app, KeyCode::Escape);
+    press(&mut app, KeyCode::KeyQ);
+    assert_eq!(app.world().resource::<CurrentGame>().rng.seed(), 77);
+    assert_eq!(app.world().resource::<NextSeed>().0, None);
+}
+
+#[test]
+fn pausing_keeps_the_game_in_progress() {
+    let mut app = flow_app();
+    start(&mut app);
+    app.world_mut().resource_mut::<CurrentGame>().score.value = 1234;
+    let position = *app
+        .world()
+        .resource::<CurrentGame>()
+        .active_piece()
+        .unwrap()
+        .1;
+
+    press(&mut app, KeyCode::Escape);
+    assert_eq!(state(&app), GameState::Paused);
+    assert!(screen_texts(&mut app).contains(&"Paused".to_string()));
+
+    press(&mut app, KeyCode::KeyP);
+    assert_eq!(state(&app), GameState::Playing);
+    let game = app.world().resource::<CurrentGame>();
+    assert_eq!(game.score.value, 1234);
+    assert_eq!(*game.active_piece().unwrap().1, position);
+    assert!(has_piece(&app));
+}
+
+#[test]
+fn restart_after_game_over_resets_the_session() {
+    let mut app = flow_app();
+    start(&mut app);
+    {
+        // a column through the spawn rows, clear of the piece being dropped
+        let mut game = app.world_mut().resource_mut::<CurrentGame>();
+        for _ in 0..4 {
+            game.apply(Action::MoveLeft);
+        }
+        let skyline = game.game_map.buffer_rows();
+        for y in skyline - 2..game.game_map.height() {
+            game.game_map.set(5, y, Presence::Yes(GameColor::Gray));
+        }
+        game.apply(Action::HardDrop);
+        game.score.value = 500;
+        game.level.value = 4;
+    }
+    app.world_mut().resource_mut::<GameResults>().lines = vec!["Grade: S1".to_string()];
+    app.update();
+    app.update();
+    assert_eq!(state(&app), GameState::GameOver);
+
+    let texts = screen_texts(&mut app);
+    assert!(texts.contains(&"Block Out".to_string()));
+    assert!(texts.contains(&"Score: 500".to_string()));
+    assert!(texts.contains(&"Grade: S1".to_string()));
+
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(state(&app), GameState::Playing);
+    let game = app.world().resource::<CurrentGame>();
+    assert_eq!(game.score.value, 0);
+    assert_eq!(game.level.value, 1);
+    assert_eq!(game.game_map.row_mask(39), 0);
+    assert_eq!(game.top_out(), None);
+    assert!(app.world().resource::<GameResults>().lines.is_empty());
+    assert!(has_piece(&app));
+}
+
+#[test]
+fn quitting_from_pause_returns_to_an_empty_main_menu() {
+    let mut app = flow_app();
+    start(&mut app);
+    press(&mut app, KeyCode::Escape);
+    press(&mut app, KeyCode::KeyQ);
+
+    assert_eq!(state(&app), GameState::MainMenu);
+    assert!(!has_piece(&app));
+    assert!(screen_texts(&mut app).is_empty());
+}
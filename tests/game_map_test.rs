@@ -0,0 +1,57 @@
+use tetris_rust_bevy_ver0_16::game_core::board::{GameMap, PieceType, Presence, mask_rows};
+use tetris_rust_bevy_ver0_16::game_core::color::GameColor;
+use tetris_rust_bevy_ver0_16::game_core::piece::Piece;
+
+#[test]
+fn mask_rows_put_column_zero_in_the_low_bit() {
+    let t = Piece::from(PieceType::T);
+    // spawn T: .#.. / ###.
+    assert_eq!(mask_rows(t.states[0]), [0b010, 0b111, 0, 0]);
+}
+
+#[test]
+fn overlaps_walls_floor_and_stack() {
+    let mut game_map = GameMap::new(10, 20);
+    assert_eq!(game_map.height(), 40);
+    let mask = Piece::from(PieceType::O).states[0];
+
+    // O occupies columns 1-2 of its box
+    assert!(!game_map.overlaps(mask, -1, 0));
+    assert!(game_map.overlaps(mask, -2, 0));
+    assert!(!game_map.overlaps(mask, 7, 0));
+    assert!(game_map.overlaps(mask, 8, 0));
+    assert!(!game_map.overlaps(mask, 3, 38));
+    assert!(game_map.overlaps(mask, 3, 39));
+    // rows above the board are open
+    assert!(!game_map.overlaps(mask, 3, -2));
+
+    game_map.set(4, 10, Presence::Yes(GameColor::Gray));
+    assert!(game_map.overlaps(mask, 3, 9));
+    assert!(!game_map.overlaps(mask, 4, 9));
+}
+
+#[test]
+fn place_writes_cells_and_colors() {
+    let mut game_map = GameMap::new(10, 20);
+    let mask = Piece::from(PieceType::I).states[0];
+    game_map.place(mask, 3, 5, GameColor::Cyan);
+
+    assert_eq!(game_map.row_mask(6), 0b1111 << 3);
+    assert_eq!(game_map.cell(3, 6), Presence::Yes(GameColor::Cyan));
+    assert_eq!(game_map.cell(2, 6), Presence::No);
+}
+
+#[test]
+fn clear_rows_keeps_colors_with_their_rows() {
+    let mut game_map = GameMap::with_buffer(4, 3, 0);
+    for x in 0..4 {
+        game_map.set(x, 2, Presence::Yes(GameColor::Red));
+    }
+    game_map.set(1, 1, Presence::Yes(GameColor::Blue));
+    assert!(game_map.is_row_full(2));
+
+    game_map.clear_rows(&[2]);
+    assert_eq!(game_map.row_mask(0), 0);
+    assert_eq!(game_map.row_mask(2), 0b0010);
+    assert_eq!(game_map.cell(1, 2), Presence::Yes(GameColor::Blue));
+}
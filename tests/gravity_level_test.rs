@@ -0,0 +1,139 @@
+use tetris_rust_bevy_ver0_16::game_core::board::{PieceType, Presence};
+use tetris_rust_bevy_ver0_16::game_core::color::GameColor;
+use tetris_rust_bevy_ver0_16::game_core::gravity::{Gravity, seconds_per_row};
+use tetris_rust_bevy_ver0_16::game_core::progression::{
+    Level, LevelGoal, LevelProgression, MAX_LEVEL,
+};
+use tetris_rust_bevy_ver0_16::game_core::tspin::TSpin;
+use tetris_rust_bevy_ver0_16::game_core::{Action, Game};
+
+fn approx(a: f32, b: f32) -> bool {
+    (a - b).abs() < 1e-4
+}
+
+#[test]
+fn curve_matches_guideline_formula() {
+    assert!(approx(seconds_per_row(1), 1.0));
+    assert!(approx(seconds_per_row(2), 0.793));
+    assert!(approx(seconds_per_row(10), 0.06415));
+    for level in 1..MAX_LEVEL {
+        assert!(seconds_per_row(level + 1) < seconds_per_row(level));
+    }
+}
+
+#[test]
+fn gravity_keeps_speeding_up_past_level_ten() {
+    assert!(Gravity::for_level(15).g() > Gravity::for_level(10).g());
+    assert!(!Gravity::for_level(15).is_instant());
+    assert!(Gravity::for_level(20).is_instant());
+}
+
+#[test]
+fn fractional_gravity_accumulates() {
+    let mut gravity = Gravity::new(0.25);
+    assert_eq!(gravity.advance(0.1), 0);
+    assert_eq!(gravity.advance(0.1), 0);
+    assert_eq!(gravity.advance(0.1), 1);
+    assert_eq!(gravity.advance(0.5), 2);
+
+    gravity.reset_progress();
+    assert_eq!(gravity.advance(0.2), 0);
+}
+
+#[test]
+fn twenty_g_drops_to_the_floor() {
+    let mut gravity = Gravity::for_level(MAX_LEVEL);
+    assert_eq!(gravity.advance(0.0), usize::MAX);
+}
+
+#[test]
+fn fixed_goal_levels_every_ten_lines_and_carries_overflow() {
+    let progression = LevelProgression::default();
+    let mut level = Level::default();
+    level.add_lines(8, &progression);
+    assert_eq!(level.value, 1);
+    level.add_lines(4, &progression);
+    assert_eq!(level.value, 2);
+    assert_eq!(level.lines_cleared_in_level, 2);
+}
+
+#[test]
+fn variable_goal_uses_awarded_lines() {
+    let progression = LevelProgression {
+        goal: LevelGoal::Variable,
+        ..Default::default()
+    };
+    assert_eq!(progression.goal_for(1), 5);
+    assert_eq!(progression.goal_for(4), 20);
+    assert_eq!(progression.lines_for(4, TSpin::None), 8);
+    assert_eq!(progression.lines_for(2, TSpin::Full), 12);
+
+    let mut level = Level::default();
+    level.add_lines(progression.lines_for(4, TSpin::None), &progression);
+    assert_eq!(level.value, 2);
+    assert_eq!(level.lines_cleared_in_level, 3);
+}
+
+#[test]
+fn level_stops_at_max() {
+    let progression = LevelProgression {
+        max_level: 3,
+        ..Default::default()
+    };
+    let mut level = Level::default();
+    level.add_lines(100, &progression);
+    assert_eq!(level.value, 3);
+}
+
+// Fills the bottom four rows but for the right column and drops an I in.
+fn tetris(game: &mut Game) {
+    let width = game.game_map.width();
+    let height = game.game_map.height();
+    for y in height - 4..height {
+        for x in 0..width - 1 {
+            game.game_map.set(x, y, Presence::Yes(GameColor::Gray));
+        }
+    }
+    game.spawn(PieceType::I);
+    game.apply(Action::RotateClockwise);
+    for _ in 0..width {
+        game.apply(Action::MoveRight);
+    }
+    game.apply(Action::HardDrop);
+}
+
+#[test]
+fn locks_drive_level_progress() {
+    let mut game = Game::new(1);
+    game.progression = Some(LevelProgression::default());
+    for _ in 0..3 {
+        tetris(&mut game);
+    }
+
+    assert_eq!(game.lines_cleared, 12);
+    assert_eq!(game.level.value, 2);
+    assert_eq!(game.level.lines_cleared_in_level, 2);
+    assert!(approx(game.gravity.seconds_per_row, seconds_per_row(2)));
+}
+
+#[test]
+fn levels_stay_put_without_a_progression() {
+    let mut game = Game::new(1);
+    for _ in 0..3 {
+        tetris(&mut game);
+    }
+    assert_eq!(game.level.value, 1);
+    assert!(approx(game.gravity.seconds_per_row, seconds_per_row(1)));
+}
+
+#[test]
+fn fixed_gravity_ignores_level_ups() {
+    let mut gravity = Gravity::default();
+    gravity.fix_level(5);
+    gravity.follow_level(9);
+    assert!(approx(gravity.seconds_per_row, seconds_per_row(5)));
+
+    let mut gravity = Gravity::default();
+    gravity.follow_level(9);
+    assert!(approx(gravity.seconds_per_row, seconds_per_row(9)));
+}
@@ -0,0 +1,95 @@
+use bevy::input::ButtonInput;
+use bevy::input::keyboard::KeyCode;
+use bevy::prelude::*;
+use tetris_rust_bevy_ver0_16::game_core::board::{BoardSize, PieceType};
+use tetris_rust_bevy_ver0_16::game_core::piece::Piece;
+use tetris_rust_bevy_ver0_16::game_core::randomizer::{PieceRandomizer, SequenceRandomizer};
+use tetris_rust_bevy_ver0_16::game_core::{Action, Game, spawn_position};
+use tetris_rust_bevy_ver0_16::resources::CurrentGame;
+use tetris_rust_bevy_ver0_16::systems::hold::hold_piece;
+
+fn hold_app(sequence: Vec<PieceType>) -> App {
+    let mut app = App::new();
+    let randomizer = PieceRandomizer(Box::new(SequenceRandomizer::new(sequence)));
+    app.insert_resource(CurrentGame(Game::with_board(
+        0,
+        &BoardSize::default(),
+        randomizer,
+    )));
+    app.insert_resource(ButtonInput::<KeyCode>::default());
+    app.add_systems(Update, hold_piece);
+    app
+}
+
+fn press_hold(app: &mut App) {
+    {
+        let mut input = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+        input.release(KeyCode::KeyC);
+        input.clear();
+        input.press(KeyCode::KeyC);
+    }
+    app.update();
+}
+
+fn active_type(app: &App) -> PieceType {
+    let game = app.world().resource::<CurrentGame>();
+    game.active_piece().unwrap().0.piece_type
+}
+
+#[test]
+fn hold_with_empty_slot_stores_piece_and_spawns_next() {
+    let mut app = hold_app(vec![PieceType::T, PieceType::I, PieceType::O]);
+    let spawns = app.world().resource::<CurrentGame>().spawns();
+
+    press_hold(&mut app);
+
+    let game = app.world().resource::<CurrentGame>();
+    assert_eq!(game.held_piece(), Some(PieceType::T));
+    assert_eq!(active_type(&app), PieceType::I);
+    assert_eq!(game.spawns(), spawns + 1);
+    assert!(!game.can_hold());
+}
+
+#[test]
+fn only_one_hold_until_next_piece() {
+    let mut app = hold_app(vec![PieceType::T, PieceType::I, PieceType::O]);
+
+    press_hold(&mut app);
+    // a second hold before the piece locks does nothing
+    press_hold(&mut app);
+    assert_eq!(active_type(&app), PieceType::I);
+    assert_eq!(
+        app.world().resource::<CurrentGame>().held_piece(),
+        Some(PieceType::T)
+    );
+
+    // the next piece may hold again and gets the held one back
+    {
+        let mut game = app.world_mut().resource_mut::<CurrentGame>();
+        game.apply(Action::RotateClockwise);
+        game.apply(Action::HardDrop);
+    }
+    assert_eq!(active_type(&app), PieceType::O);
+    press_hold(&mut app);
+
+    let game = app.world().resource::<CurrentGame>();
+    let (piece, position) = game.active_piece().unwrap();
+    assert_eq!(piece.piece_type, PieceType::T);
+    assert_eq!(piece.current_state, 0);
+    let spawn = spawn_position(&game.game_map, &Piece::from(PieceType::T));
+    assert_eq!(*position, spawn);
+    assert_eq!(game.held_piece(), Some(PieceType::O));
+}
+
+#[test]
+fn hold_does_nothing_once_the_sequence_is_used_up() {
+    let mut app = hold_app(vec![PieceType::T]);
+    let spawns = app.world().resource::<CurrentGame>().spawns();
+
+    press_hold(&mut app);
+
+    let game = app.world().resource::<CurrentGame>();
+    assert_eq!(active_type(&app), PieceType::T);
+    assert_eq!(game.held_piece(), None);
+    assert_eq!(game.spawns(), spawns);
+}
@@ -0,0 +1,158 @@
+use bevy::prelude::*;
+use std::time::Duration;
+use tetris_rust_bevy_ver0_16::game_core::board::{PieceType, Presence};
+use tetris_rust_bevy_ver0_16::game_core::color::GameColor;
+use tetris_rust_bevy_ver0_16::game_core::scoring::Score;
+use tetris_rust_bevy_ver0_16::game_core::{Action, Game};
+use tetris_rust_bevy_ver0_16::resources::{CurrentGame, LockEvent};
+use tetris_rust_bevy_ver0_16::stats::{GameClock, GameStats, format_time};
+use tetris_rust_bevy_ver0_16::systems::hud::{
+    HudField, spawn_hud, streak_label, update_clock_hud, update_score_hud,
+};
+use tetris_rust_bevy_ver0_16::systems::movement::step_game;
+
+fn hud_app() -> App {
+    let mut app = App::new();
+    app.init_resource::<CurrentGame>();
+    app.init_resource::<GameStats>();
+    app.init_resource::<GameClock>();
+    app.add_event::<LockEvent>();
+    app.add_systems(Startup, spawn_hud);
+    app.add_systems(
+        Update,
+        (
+            update_score_hud.run_if(resource_changed::<CurrentGame>),
+            update_clock_hud.run_if(resource_changed::<GameClock>),
+        ),
+    );
+    app.update();
+    app
+}
+
+fn field(app: &mut App, wanted: HudField) -> String {
+    let mut query = app.world_mut().query::<(&HudField, &Text)>();
+    query
+        .iter(app.world())
+        .find(|(field, _)| **field == wanted)
+        .map(|(_, text)| text.0.clone())
+        .unwrap()
+}
+
+#[test]
+fn hud_shows_initial_values() {
+    let mut app = hud_app();
+    assert_eq!(field(&mut app, HudField::Score), "Score: 0");
+    assert_eq!(field(&mut app, HudField::Level), "Level: 1");
+    assert_eq!(field(&mut app, HudField::Lines), "Lines: 0");
+    assert_eq!(field(&mut app, HudField::Time), "Time: 0:00.00");
+    assert_eq!(field(&mut app, HudField::PiecesPerSecond), "PPS: 0.00");
+}
+
+#[test]
+fn locks_update_lines_and_pieces() {
+    let mut app = hud_app();
+    {
+        let mut game = app.world_mut().resource_mut::<CurrentGame>();
+        let width = game.game_map.width();
+        let height = game.game_map.height();
+        for y in height - 4..height {
+            for x in 0..width - 1 {
+                game.game_map.set(x, y, Presence::Yes(GameColor::Gray));
+            }
+        }
+        game.spawn(PieceType::I);
+        game.apply(Action::RotateClockwise);
+        for _ in 0..width {
+            game.apply(Action::MoveRight);
+        }
+        game.apply(Action::HardDrop);
+    }
+    app.update();
+    assert_eq!(field(&mut app, HudField::Lines), "Lines: 4");
+    assert_eq!(field(&mut app, HudField::Pieces), "Pieces: 1");
+}
+
+#[test]
+fn fields_are_only_rewritten_when_their_resource_changes() {
+    let mut app = hud_app();
+    set_field_text(&mut app, HudField::Score, "untouched");
+    app.update();
+    assert_eq!(field(&mut app, HudField::Score), "untouched");
+
+    {
+        let mut game = app.world_mut().resource_mut::<CurrentGame>();
+        game.score.value = 40;
+        game.level.value = 3;
+    }
+    app.update();
+    assert_eq!(field(&mut app, HudField::Score), "Score: 40");
+    assert_eq!(field(&mut app, HudField::Level), "Level: 3");
+}
+
+fn set_field_text(app: &mut App, wanted: HudField, value: &str) {
+    let mut query = app.world_mut().query::<(&HudField, &mut Text)>();
+    for (field, mut text) in query.iter_mut(app.world_mut()) {
+        if *field == wanted {
+            text.0 = value.to_string();
+        }
+    }
+}
+
+#[test]
+fn stepping_the_game_leaves_the_score_alone_until_a_piece_locks() {
+    let mut app = hud_app();
+    app.init_resource::<Time>();
+    app.add_systems(Update, step_game.before(update_score_hud));
+    let mut game = Game::new(0);
+    game.lock_delay.delay = 0.5;
+    for _ in 0..game.game_map.height() {
+        game.apply(Action::SoftDrop);
+    }
+    let score = game.score.value;
+    app.insert_resource(CurrentGame(game));
+    app.update();
+
+    set_field_text(&mut app, HudField::Score, "untouched");
+    for _ in 0..3 {
+        app.world_mut()
+            .resource_mut::<Time>()
+            .advance_by(Duration::from_millis(16));
+        app.update();
+    }
+    assert_eq!(field(&mut app, HudField::Score), "untouched");
+
+    app.world_mut()
+        .resource_mut::<Time>()
+        .advance_by(Duration::from_millis(600));
+    app.update();
+    assert_eq!(app.world().resource::<CurrentGame>().pieces_placed, 1);
+    assert_eq!(field(&mut app, HudField::Score), format!("Score: {score}"));
+}
+
+#[test]
+fn clock_drives_time_and_pps() {
+    let mut app = hud_app();
+    app.world_mut().resource_mut::<CurrentGame>().pieces_placed = 30;
+    app.world_mut().resource_mut::<GameClock>().elapsed = Duration::from_millis(75_500);
+    app.update();
+    assert_eq!(field(&mut app, HudField::Time), "Time: 1:15.50");
+    assert_eq!(field(&mut app, HudField::PiecesPerSecond), "PPS: 0.40");
+}
+
+#[test]
+fn streak_shows_combo_and_back_to_back() {
+    let mut score = Score::default();
+    assert_eq!(streak_label(&score), "");
+    score.combo = Some(0);
+    assert_eq!(streak_label(&score), "");
+    score.combo = Some(3);
+    score.back_to_back = true;
+    assert_eq!(streak_label(&score), "Combo x3  B2B");
+}
+
+#[test]
+fn time_format_rolls_over_minutes() {
+    assert_eq!(format_time(0.0), "0:00.00");
+    assert_eq!(format_time(59.99), "0:59.99");
+    assert_eq!(format_time(125.25), "2:05.25");
+}
@@ -1,12 +1,11 @@
 use bevy::input::ButtonInput;
 use bevy::input::keyboard::KeyCode;
 use bevy::prelude::*;
-use bevy::state::app::StatesPlugin;
-use tetris_rust_bevy_ver0_16::components::{Piece, Position};
-use tetris_rust_bevy_ver0_16::game_types::GameMap;
-use tetris_rust_bevy_ver0_16::resources::Score;
-use tetris_rust_bevy_ver0_16::state::GameState;
-use tetris_rust_bevy_ver0_16::systems::input::{can_rotate, handle_input};
+use tetris_rust_bevy_ver0_16::game_core::Game;
+use tetris_rust_bevy_ver0_16::game_core::board::GameMap;
+use tetris_rust_bevy_ver0_16::game_core::piece::{Piece, Position};
+use tetris_rust_bevy_ver0_16::resources::{CurrentGame, LockEvent};
+use tetris_rust_bevy_ver0_16::systems::input::handle_input;
 
 #[test]
 fn rotate_block_within_bounds() {
@@ -17,28 +16,17 @@ fn rotate_block_within_bounds() {
         ..Default::default()
     };
 
-    assert!(can_rotate(&piece, &pos, &game_map));
+    assert!(!game_map.collides(&piece, piece.current_state, pos.x, pos.y));
 }
 
 #[test]
 fn hard_drop_increases_score() {
     let mut app = App::new();
-    app.add_plugins(StatesPlugin);
-    app.init_resource::<GameMap>();
-    app.insert_resource(Score::default());
-    app.insert_state(GameState::Playing);
-
+    app.insert_resource(CurrentGame(Game::new(0)));
+    app.add_event::<LockEvent>();
     app.insert_resource(ButtonInput::<KeyCode>::default());
     app.add_systems(Update, handle_input);
 
-    // spawn a small piece somewhere near top
-    let piece = Piece {
-        states: [1632, 1632, 1632, 1632],
-        ..Default::default()
-    };
-    let pos = Position { x: 0, y: 0 };
-    app.world_mut().spawn((piece, pos));
-
     // press Space to trigger hard drop
     {
         let mut input = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
@@ -47,7 +35,27 @@ fn hard_drop_increases_score() {
 
     app.update();
 
-    // score should have increased
-    let score = app.world().resource::<Score>();
-    assert!(score.value > 0);
+    // score should have increased and the lock gone out
+    let game = app.world().resource::<CurrentGame>();
+    assert!(game.score.value > 0);
+    assert_eq!(game.pieces_placed, 1);
+    let events = app.world().resource::<Events<LockEvent>>();
+    assert_eq!(events.len(), 1);
+}
+
+#[test]
+fn rotation_keys_turn_the_active_piece() {
+    let mut app = App::new();
+    app.insert_resource(CurrentGame(Game::new(0)));
+    app.add_event::<LockEvent>();
+    app.insert_resource(ButtonInput::<KeyCode>::default());
+    app.add_systems(Update, handle_input);
+
+    app.world_mut()
+        .resource_mut::<ButtonInput<KeyCode>>()
+        .press(KeyCode::KeyX);
+    app.update();
+
+    let game = app.world().resource::<CurrentGame>();
+    assert_eq!(game.active_piece().unwrap().0.current_state, 1);
 }
@@ -1,29 +1,50 @@
-use bevy::prelude::*;
 use tetris_rust_bevy_ver0_16::game_constants::NUM_BLOCKS_X;
-use tetris_rust_bevy_ver0_16::game_types::{GameMap, Presence};
-use tetris_rust_bevy_ver0_16::resources::{Level, Score};
-use tetris_rust_bevy_ver0_16::systems::lines::clear_lines;
+use tetris_rust_bevy_ver0_16::game_core::Game;
+use tetris_rust_bevy_ver0_16::game_core::board::{GameMap, Presence, is_perfect_clear};
 
 #[test]
-fn clear_full_line_increases_score_and_shifts_rows() {
-    let mut app = App::new();
-    app.insert_resource(GameMap(vec![
+fn clear_full_line_shifts_rows() {
+    let mut game = Game::new(0);
+    let mut rows =
         vec![
-            Presence::Yes(
-                tetris_rust_bevy_ver0_16::game_color::GameColor::Red
-            );
-            NUM_BLOCKS_X
+            vec![
+                Presence::Yes(tetris_rust_bevy_ver0_16::game_core::color::GameColor::Red);
+                NUM_BLOCKS_X
+            ];
+            4
         ];
-        4
-    ]));
-    app.insert_resource(Score::default());
-    app.insert_resource(Level::default());
-    app.add_systems(Update, clear_lines);
+    rows.insert(0, vec![Presence::No; NUM_BLOCKS_X]);
+    rows[0][0] = Presence::Yes(tetris_rust_bevy_ver0_16::game_core::color::GameColor::Blue);
+    game.game_map = GameMap::from_cells(&rows);
 
-    app.update();
+    assert_eq!(game.clear_full_rows(), 4);
 
-    let score = app.world().resource::<Score>();
-    let level = app.world().resource::<Level>();
-    assert!(score.value >= 100);
-    assert!(level.value <= 1);
+    let game_map = &game.game_map;
+    assert_eq!(game_map.height(), 5);
+    assert_eq!(
+        game_map.cell(0, 4),
+        Presence::Yes(tetris_rust_bevy_ver0_16::game_core::color::GameColor::Blue)
+    );
+    assert!((0..4).all(|y| game_map.row_mask(y) == 0));
+}
+
+#[test]
+fn perfect_clear_needs_only_full_or_empty_rows() {
+    let full = vec![
+        Presence::Yes(tetris_rust_bevy_ver0_16::game_core::color::GameColor::Red);
+        NUM_BLOCKS_X
+    ];
+    let empty = vec![Presence::No; NUM_BLOCKS_X];
+    let mut partial = empty.clone();
+    partial[3] = full[0];
+
+    assert!(is_perfect_clear(&GameMap::from_cells(&[
+        empty.clone(),
+        full.clone()
+    ])));
+    assert!(!is_perfect_clear(&GameMap::from_cells(&[partial, full])));
+    assert!(!is_perfect_clear(&GameMap::from_cells(&[
+        empty.clone(),
+        empty
+    ])));
 }
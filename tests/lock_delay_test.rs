@@ -0,0 +1,106 @@
+use bevy::prelude::*;
+use std::time::Duration;
+use tetris_rust_bevy_ver0_16::game_core::lock_delay::{LockDelay, LockTimer};
+use tetris_rust_bevy_ver0_16::game_core::{Action, Game};
+use tetris_rust_bevy_ver0_16::resources::{CurrentGame, LockEvent};
+use tetris_rust_bevy_ver0_16::systems::movement::step_game;
+
+#[test]
+fn timer_locks_after_delay_on_ground() {
+    let lock_delay = LockDelay::default();
+    let mut timer = LockTimer::default();
+    timer.reset(10);
+
+    assert!(!timer.tick(0.3, true, &lock_delay));
+    assert!(timer.tick(0.3, true, &lock_delay));
+}
+
+#[test]
+fn timer_does_not_run_in_the_air() {
+    let lock_delay = LockDelay::default();
+    let mut timer = LockTimer::default();
+    timer.reset(10);
+
+    assert!(!timer.tick(0.4, true, &lock_delay));
+    assert!(!timer.tick(0.4, false, &lock_delay));
+    assert!(!timer.tick(0.4, true, &lock_delay));
+}
+
+#[test]
+fn moves_on_ground_reset_timer_until_limit() {
+    let lock_delay = LockDelay::default();
+    let mut timer = LockTimer::default();
+    timer.reset(10);
+
+    for _ in 0..lock_delay.max_resets - 1 {
+        assert!(!timer.tick(0.4, true, &lock_delay));
+        timer.on_move(&lock_delay);
+    }
+    assert_eq!(timer.resets(), lock_delay.max_resets - 1);
+    assert!(!timer.tick(0.4, true, &lock_delay));
+
+    // the last reset is spent, so the piece locks on the next grounded tick
+    timer.on_move(&lock_delay);
+    assert!(timer.tick(0.0, true, &lock_delay));
+}
+
+#[test]
+fn new_lowest_row_refills_resets() {
+    let lock_delay = LockDelay::default();
+    let mut timer = LockTimer::default();
+    timer.reset(10);
+
+    timer.tick(0.1, true, &lock_delay);
+    timer.on_move(&lock_delay);
+    timer.on_move(&lock_delay);
+    assert_eq!(timer.resets(), 2);
+
+    timer.observe(10);
+    assert_eq!(timer.resets(), 2);
+    timer.observe(11);
+    assert_eq!(timer.resets(), 0);
+}
+
+#[test]
+fn new_piece_starts_a_fresh_timer() {
+    let lock_delay = LockDelay::default();
+    let mut timer = LockTimer::default();
+    timer.reset(10);
+    timer.tick(0.1, true, &lock_delay);
+    timer.on_move(&lock_delay);
+
+    timer.reset(0);
+    assert_eq!(timer.resets(), 0);
+    assert!(!timer.tick(0.4, true, &lock_delay));
+}
+
+// Soft drops the active piece until it rests on the floor.
+fn rest_on_floor(game: &mut Game) {
+    for _ in 0..game.game_map.height() {
+        game.apply(Action::SoftDrop);
+    }
+}
+
+#[test]
+fn lock_piece_waits_for_delay() {
+    let mut app = App::new();
+    app.init_resource::<Time>();
+    app.add_event::<LockEvent>();
+    let mut game = Game::new(0);
+    rest_on_floor(&mut game);
+    app.insert_resource(CurrentGame(game));
+    app.add_systems(Update, step_game);
+
+    app.world_mut()
+        .resource_mut::<Time>()
+        .advance_by(Duration::from_millis(300));
+    app.update();
+    assert_eq!(app.world().resource::<CurrentGame>().pieces_placed, 0);
+
+    app.world_mut()
+        .resource_mut::<Time>()
+        .advance_by(Duration::from_millis(300));
+    app.update();
+    assert_eq!(app.world().resource::<CurrentGame>().pieces_placed, 1);
+    assert_eq!(app.world().resource::<Events<LockEvent>>().len(), 1);
+}
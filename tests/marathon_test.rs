@@ -0,0 +1,197 @@
+use bevy::prelude::*;
+use bevy::state::app::StatesPlugin;
+use tetris_rust_bevy_ver0_16::game_core::board::{BoardSize, PieceType, Presence};
+use tetris_rust_bevy_ver0_16::game_core::color::GameColor;
+use tetris_rust_bevy_ver0_16::game_core::progression::LevelGoal;
+use tetris_rust_bevy_ver0_16::game_core::randomizer::PieceRandomizer;
+use tetris_rust_bevy_ver0_16::game_core::{Action, Game};
+use tetris_rust_bevy_ver0_16::modes::marathon::{MARATHON, MarathonModePlugin, MarathonSettings};
+use tetris_rust_bevy_ver0_16::modes::{ENDLESS, EndlessModePlugin, SelectedMode};
+use tetris_rust_bevy_ver0_16::records::BestTimes;
+use tetris_rust_bevy_ver0_16::resources::{CurrentGame, LockEvent};
+use tetris_rust_bevy_ver0_16::state::{GameResults, GameState};
+use tetris_rust_bevy_ver0_16::stats::{GameClock, GameStats};
+use tetris_rust_bevy_ver0_16::systems::game_flow::start_game;
+use tetris_rust_bevy_ver0_16::systems::hud::{HudField, spawn_hud};
+
+fn marathon_app(settings: MarathonSettings) -> App {
+    let mut app = App::new();
+    app.add_plugins(StatesPlugin);
+    app.init_resource::<GameStats>();
+    app.init_resource::<GameClock>();
+    app.insert_resource(CurrentGame(Game::unstarted(
+        3,
+        &BoardSize::default(),
+        PieceRandomizer::default(),
+    )));
+    app.init_state::<GameState>();
+    app.add_systems(Startup, spawn_hud);
+    app.add_systems(OnEnter(GameState::Playing), start_game);
+    app.add_event::<LockEvent>();
+    app.add_plugins((EndlessModePlugin, MarathonModePlugin));
+    app.insert_resource(settings);
+    app.insert_resource(SelectedMode(MARATHON.id));
+    app.world_mut()
+        .resource_mut::<NextState<GameState>>()
+        .set(GameState::Playing);
+    app.update();
+    app
+}
+
+// Fills the bottom four rows but for the right column and drops an I in.
+fn tetris(app: &mut App) {
+    let mut game = app.world_mut().resource_mut::<CurrentGame>();
+    let width = game.game_map.width();
+    let height = game.game_map.height();
+    for y in height - 4..height {
+        for x in 0..width - 1 {
+            game.game_map.set(x, y, Presence::Yes(GameColor::Gray));
+        }
+    }
+    game.spawn(PieceType::I);
+    game.apply(Action::RotateClockwise);
+    for _ in 0..width {
+        game.apply(Action::MoveRight);
+    }
+    let locked = game.apply(Action::HardDrop).unwrap();
+    assert_eq!(locked.lines_cleared, 4);
+    app.world_mut().send_event(LockEvent(locked));
+    app.update();
+}
+
+fn level(app: &App) -> u32 {
+    app.world().resource::<CurrentGame>().level.value
+}
+
+fn state(app: &App) -> GameState {
+    *app.world().resource::<State<GameState>>().get()
+}
+
+fn banner(app: &mut App) -> String {
+    let mut query = app.world_mut().query::<(&HudField, &Text)>();
+    query
+        .iter(app.world())
+        .find(|(field, _)| **field == HudField::Banner)
+        .map(|(_, text)| text.0.clone())
+        .unwrap()
+}
+
+#[test]
+fn levels_stop_at_the_cap_and_the_goal_finishes_the_game() {
+    let mut app = marathon_app(MarathonSettings::default());
+    for _ in 0..37 {
+        tetris(&mut app);
+    }
+    // 148 lines: capped at 15 and still short of the goal
+    assert_eq!(level(&app), 15);
+    assert_eq!(state(&app), GameState::Playing);
+
+    tetris(&mut app);
+    app.update();
+    assert_eq!(state(&app), GameState::Finished);
+    let results = app.world().resource::<GameResults>();
+    assert_eq!(results.headline, "Marathon Complete");
+    assert!(results.lines.contains(&"Lines: 152".to_string()));
+    assert!(results.lines.contains(&"New best clear!".to_string()));
+    assert!(
+        app.world()
+            .resource::<BestTimes>()
+            .get(MARATHON.id, 150)
+            .is_some()
+    );
+    assert_eq!(banner(&mut app), "");
+}
+
+#[test]
+fn endless_marathon_plays_past_the_goal() {
+    let mut app = marathon_app(MarathonSettings {
+        line_goal: 150,
+        level_cap: 20,
+        endless: true,
+        ..MarathonSettings::default()
+    });
+    for _ in 0..37 {
+        tetris(&mut app);
+    }
+    assert_eq!(banner(&mut app), "");
+    assert!(
+        app.world()
+            .resource::<BestTimes>()
+            .get(MARATHON.id, 150)
+            .is_none()
+    );
+
+    // the goal is announced and recorded once, then play goes on
+    tetris(&mut app);
+    app.update();
+    assert_eq!(state(&app), GameState::Playing);
+    assert_eq!(banner(&mut app), "150 lines in 0:00.00");
+    let clear = *app
+        .world()
+        .resource::<BestTimes>()
+        .get(MARATHON.id, 150)
+        .unwrap();
+    assert_eq!(clear.seed, 3);
+
+    for _ in 0..12 {
+        tetris(&mut app);
+    }
+    app.update();
+    assert_eq!(state(&app), GameState::Playing);
+    assert_eq!(level(&app), 20);
+    assert_eq!(
+        *app.world()
+            .resource::<BestTimes>()
+            .get(MARATHON.id, 150)
+            .unwrap(),
+        clear
+    );
+}
+
+#[test]
+fn only_the_selected_mode_levels_up() {
+    let mut app = marathon_app(MarathonSettings::default());
+    assert_eq!(
+        app.world().resource::<CurrentGame>().progression,
+        Some(MarathonSettings::default().progression())
+    );
+    tetris(&mut app);
+    tetris(&mut app);
+    tetris(&mut app);
+    // 12 lines is one level, not two from both modes' progression
+    assert_eq!(level(&app), 2);
+
+    // switching modes mid-game leaves the running game's progression alone
+    app.insert_resource(SelectedMode(ENDLESS.id));
+    tetris(&mut app);
+    tetris(&mut app);
+    assert_eq!(level(&app), 3);
+}
+
+#[test]
+fn variable_goal_levels_up_on_awarded_lines() {
+    let mut app = marathon_app(MarathonSettings {
+        level_goal: LevelGoal::Variable,
+        ..MarathonSettings::default()
+    });
+    // a tetris awards eight lines against level one's goal of five
+    tetris(&mut app);
+    assert_eq!(level(&app), 2);
+
+    let mut app = marathon_app(MarathonSettings::default());
+    tetris(&mut app);
+    assert_eq!(level(&app), 1);
+}
+
+#[test]
+fn settings_step_between_presets() {
+    let mut settings = MarathonSettings::default();
+    settings.step_goal(1);
+    settings.step_level_cap(1);
+    assert_eq!((settings.line_goal, settings.level_cap), (200, 20));
+    settings.step_goal(1);
+    assert_eq!(settings.line_goal, 200);
+    settings.step_level_cap(-1);
+    assert_eq!(settings.level_cap, 15);
+    assert_eq!(settings.progression().max_level, 15);
+}
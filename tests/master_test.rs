@@ -0,0 +1,218 @@
+use bevy::prelude::*;
+use bevy::state::app::StatesPlugin;
+use tetris_rust_bevy_ver0_16::game_constants::MATRIX_ROWS;
+use tetris_rust_bevy_ver0_16::game_core::Game;
+use tetris_rust_bevy_ver0_16::game_core::board::{BoardSize, PieceType, Presence};
+use tetris_rust_bevy_ver0_16::game_core::color::GameColor;
+use tetris_rust_bevy_ver0_16::game_core::entry_delay::{EntryDelay, EntryTimer};
+use tetris_rust_bevy_ver0_16::game_core::lock_delay::LockDelay;
+use tetris_rust_bevy_ver0_16::game_core::progression::MAX_LEVEL;
+use tetris_rust_bevy_ver0_16::game_core::randomizer::PieceRandomizer;
+use tetris_rust_bevy_ver0_16::game_core::scoring::Score;
+use tetris_rust_bevy_ver0_16::game_core::tspin::TSpin;
+use tetris_rust_bevy_ver0_16::modes::fresh_game;
+use tetris_rust_bevy_ver0_16::modes::master::{
+    GRADES, MAX_MASTER_LEVEL, MasterGrade, SECTIONS, advance_level, report_master_grade,
+    section_timing, setup_master,
+};
+use tetris_rust_bevy_ver0_16::resources::CurrentGame;
+use tetris_rust_bevy_ver0_16::state::{GameResults, GameState};
+use tetris_rust_bevy_ver0_16::systems::game_flow::{spawn_game_over_screen, start_game};
+
+const BOTTOM: usize = MATRIX_ROWS - 1;
+
+#[test]
+fn level_stops_at_section_ends_until_lines_clear() {
+    assert_eq!(advance_level(0, 0), 1);
+    assert_eq!(advance_level(10, 2), 13);
+    assert_eq!(advance_level(99, 0), 99);
+    assert_eq!(advance_level(99, 1), 100);
+    assert_eq!(advance_level(998, 0), 998);
+    assert_eq!(advance_level(997, 4), MAX_MASTER_LEVEL);
+}
+
+#[test]
+fn sections_set_the_delays() {
+    assert_eq!(section_timing(0), SECTIONS[0]);
+    assert_eq!(section_timing(250), SECTIONS[2]);
+    assert_eq!(section_timing(MAX_MASTER_LEVEL), SECTIONS[9]);
+
+    let mut lock_delay = LockDelay::default();
+    let mut entry_delay = EntryDelay::default();
+    section_timing(0).apply(&mut lock_delay, &mut entry_delay);
+    assert_eq!(lock_delay.delay, 0.5);
+    assert!(!entry_delay.is_instant());
+    assert!(entry_delay.line_clear > entry_delay.are);
+}
+
+#[test]
+fn grade_climbs_with_clears_and_drains_over_time() {
+    let mut grade = MasterGrade::default();
+    grade.award(0, 500);
+    assert_eq!(grade.points, 0.0);
+    for _ in 0..3 {
+        grade.award(4, 0);
+    }
+    assert_eq!(grade.label(), "8");
+
+    let points = grade.points;
+    grade.decay(1.0);
+    assert!(grade.points < points);
+    grade.decay(1000.0);
+    assert_eq!(grade.points, 0.0);
+    assert_eq!(grade.label(), "8");
+}
+
+#[test]
+fn grandmaster_needs_s9_at_the_last_level() {
+    let mut grade = MasterGrade {
+        grade: GRADES.len() - 2,
+        points: 0.0,
+    };
+    grade.finish(MAX_MASTER_LEVEL - 1);
+    assert_eq!(grade.label(), "S9");
+    grade.finish(MAX_MASTER_LEVEL);
+    assert_eq!(grade.label(), "GM");
+
+    let mut grade = MasterGrade::default();
+    grade.finish(MAX_MASTER_LEVEL);
+    assert_eq!(grade.label(), "9");
+}
+
+#[test]
+fn entry_timer_runs_line_clear_then_are() {
+    let delay = EntryDelay {
+        are: 0.2,
+        line_clear: 0.3,
+    };
+    let mut timer = EntryTimer::default();
+    timer.start(&delay, true);
+    assert!(timer.is_clearing());
+    assert!(!timer.tick(0.35));
+    assert!(!timer.is_clearing());
+    assert!(timer.is_waiting());
+    assert!(!timer.tick(0.1));
+    assert!(timer.tick(0.15));
+    assert!(!timer.is_waiting());
+
+    timer.start(&delay, false);
+    assert!(!timer.is_clearing());
+    assert!(timer.tick(0.25));
+}
+
+fn master_game() -> Game {
+    let mut game = Game::unstarted(0, &BoardSize::default(), PieceRandomizer::default());
+    game.gravity.fix_level(MAX_LEVEL);
+    assert!(game.gravity.is_instant());
+    game.entry_delay = EntryDelay {
+        are: 0.2,
+        line_clear: 0.3,
+    };
+    game.start();
+    game
+}
+
+#[test]
+fn next_piece_waits_for_line_clear_and_are_then_lands_at_20g() {
+    let mut game = master_game();
+    for x in (0..3).chain(7..10) {
+        game.game_map.set(x, BOTTOM, Presence::Yes(GameColor::Gray));
+    }
+    // at 20G the I enters already resting on the bottom row
+    game.spawn(PieceType::I);
+
+    assert!(game.tick(0.6).is_some());
+    assert!(game.active_piece().is_none());
+    // the full row stays up during the line clear delay
+    assert!(game.game_map.is_row_full(BOTTOM));
+
+    game.tick(0.2);
+    assert!(game.game_map.is_row_full(BOTTOM));
+    game.tick(0.2);
+    assert_eq!(game.game_map.row_mask(BOTTOM), 0);
+    assert!(game.active_piece().is_none());
+
+    game.tick(0.25);
+    let (piece, position) = game.active_piece().expect("piece after ARE");
+    assert!(
+        game.game_map
+            .collides(piece, piece.current_state, position.x, position.y + 1)
+    );
+}
+
+#[test]
+fn a_fresh_master_game_starts_at_level_0_with_20g_and_section_delays() {
+    let mut app = App::new();
+    app.insert_resource(CurrentGame(Game::unstarted(
+        0,
+        &BoardSize::default(),
+        PieceRandomizer::default(),
+    )));
+    app.insert_resource(MasterGrade {
+        grade: 5,
+        points: 40.0,
+    });
+    app.add_systems(
+        Update,
+        (setup_master.run_if(fresh_game), start_game).chain(),
+    );
+    app.update();
+
+    let game = app.world().resource::<CurrentGame>();
+    assert!(game.is_started());
+    assert_eq!(game.level.value, 0);
+    assert!(game.gravity.is_instant());
+    let mut lock_delay = LockDelay::default();
+    let mut entry_delay = EntryDelay::default();
+    SECTIONS[0].apply(&mut lock_delay, &mut entry_delay);
+    assert_eq!(game.lock_delay, lock_delay);
+    assert_eq!(game.entry_delay, entry_delay);
+    assert_eq!(
+        *app.world().resource::<MasterGrade>(),
+        MasterGrade::default()
+    );
+
+    // resuming a started game leaves it alone
+    app.world_mut().resource_mut::<CurrentGame>().level.value = 300;
+    app.update();
+    assert_eq!(app.world().resource::<CurrentGame>().level.value, 300);
+}
+
+#[test]
+fn game_over_screen_shows_the_grade() {
+    let mut app = App::new();
+    app.add_plugins(StatesPlugin);
+    app.init_resource::<CurrentGame>();
+    app.init_resource::<GameResults>();
+    app.insert_resource(MasterGrade {
+        grade: 3,
+        points: 0.0,
+    });
+    app.init_state::<GameState>();
+    app.add_systems(
+        OnEnter(GameState::GameOver),
+        (
+            report_master_grade.before(spawn_game_over_screen),
+            spawn_game_over_screen,
+        ),
+    );
+    app.world_mut()
+        .resource_mut::<NextState<GameState>>()
+        .set(GameState::GameOver);
+    app.update();
+
+    let mut query = app.world_mut().query::<&Text>();
+    let texts: Vec<String> = query.iter(app.world()).map(|text| text.0.clone()).collect();
+    assert!(texts.contains(&format!("Grade: {}", GRADES[3])));
+}
+
+#[test]
+fn master_levels_score_on_the_guideline_scale() {
+    let mut score = Score::default();
+    score.award_lock(4, TSpin::None, false, 900);
+    assert_eq!(score.value, 800 * MAX_LEVEL);
+
+    score.combo = None;
+    score.award_lock(1, TSpin::None, false, 0);
+    assert_eq!(score.value, 800 * MAX_LEVEL + 100);
+}
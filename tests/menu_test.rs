@@ -0,0 +1,256 @@
+use bevy::input::ButtonInput;
+use bevy::input::keyboard::KeyCode;
+use bevy::prelude::*;
+use bevy::state::app::StatesPlugin;
+use tetris_rust_bevy_ver0_16::auto_repeat::InputSettings;
+use tetris_rust_bevy_ver0_16::game_core::randomizer::RandomizerKind;
+use tetris_rust_bevy_ver0_16::menu::{MenuCursor, MenuPlugin, MenuState};
+use tetris_rust_bevy_ver0_16::modes::{
+    EndlessModePlugin, GameModeInfo, GameModes, Leaderboard, Ranking, RegisterGameMode,
+    SelectedMode, Setting,
+};
+use tetris_rust_bevy_ver0_16::records::{HighScores, ReplayEntry, ReplayLog, ScoreEntry};
+use tetris_rust_bevy_ver0_16::resources::{CurrentGame, RandomizerChoice};
+use tetris_rust_bevy_ver0_16::state::GameState;
+
+const TEST_MODE: GameModeInfo = GameModeInfo {
+    id: "test",
+    name: "Test",
+    description: "registered by a test plugin",
+};
+
+fn menu_app() -> App {
+    let mut app = App::new();
+    app.add_plugins(StatesPlugin);
+    app.init_resource::<InputSettings>();
+    app.init_resource::<CurrentGame>();
+    app.insert_resource(ButtonInput::<KeyCode>::default());
+    app.init_state::<GameState>();
+    app.add_plugins((MenuPlugin, EndlessModePlugin));
+    app.register_game_mode(TEST_MODE);
+    app.update();
+    app
+}
+
+fn press(app: &mut App, key: KeyCode) {
+    {
+        let mut input = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+        input.release_all();
+        input.clear();
+        input.press(key);
+    }
+    app.update();
+    app.world_mut()
+        .resource_mut::<ButtonInput<KeyCode>>()
+        .clear();
+    app.update();
+}
+
+fn menu_state(app: &App) -> MenuState {
+    *app.world().resource::<State<MenuState>>().get()
+}
+
+fn game_state(app: &App) -> GameState {
+    *app.world().resource::<State<GameState>>().get()
+}
+
+fn screen_texts(app: &mut App) -> Vec<String> {
+    let mut query = app.world_mut().query::<&Text>();
+    query.iter(app.world()).map(|text| text.0.clone()).collect()
+}
+
+#[test]
+fn main_menu_lists_every_screen() {
+    let mut app = menu_app();
+    assert_eq!(menu_state(&app), MenuState::Main);
+    let texts = screen_texts(&mut app);
+    for entry in ["Play", "Settings", "High Scores", "Replays", "Quit"] {
+        assert!(texts.contains(&entry.to_string()), "missing {entry}");
+    }
+}
+
+#[test]
+fn cursor_wraps_around_the_menu() {
+    let mut app = menu_app();
+    press(&mut app, KeyCode::ArrowUp);
+    assert_eq!(app.world().resource::<MenuCursor>().index, 4);
+    press(&mut app, KeyCode::ArrowDown);
+    assert_eq!(app.world().resource::<MenuCursor>().index, 0);
+}
+
+#[test]
+fn registered_modes_can_be_selected_and_started() {
+    let mut app = menu_app();
+    assert_eq!(app.world().resource::<GameModes>().len(), 2);
+
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(menu_state(&app), MenuState::ModeSelect);
+    assert!(
+        screen_texts(&mut app)
+            .iter()
+            .any(|text| text.starts_with("Test"))
+    );
+
+    press(&mut app, KeyCode::ArrowDown);
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(game_state(&app), GameState::Playing);
+    assert_eq!(app.world().resource::<SelectedMode>().0, "test");
+    assert!(screen_texts(&mut app).is_empty());
+}
+
+#[test]
+fn escape_goes_back_to_the_main_menu() {
+    let mut app = menu_app();
+    press(&mut app, KeyCode::ArrowDown);
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(menu_state(&app), MenuState::Settings);
+
+    press(&mut app, KeyCode::Escape);
+    assert_eq!(menu_state(&app), MenuState::Main);
+    assert!(screen_texts(&mut app).contains(&"Play".to_string()));
+}
+
+#[test]
+fn settings_change_with_left_and_right() {
+    let mut app = menu_app();
+    press(&mut app, KeyCode::ArrowDown);
+    press(&mut app, KeyCode::Enter);
+
+    let das = app.world().resource::<InputSettings>().das_ms;
+    press(&mut app, KeyCode::ArrowRight);
+    assert_eq!(app.world().resource::<InputSettings>().das_ms, das + 10.0);
+    assert!(screen_texts(&mut app).contains(&format!("DAS: {} ms", das + 10.0)));
+
+    // previews are the sixth setting
+    for _ in 0..5 {
+        press(&mut app, KeyCode::ArrowDown);
+    }
+    let previews = app
+        .world()
+        .resource::<CurrentGame>()
+        .next_queue
+        .preview_count();
+    press(&mut app, KeyCode::ArrowLeft);
+    assert_eq!(
+        app.world()
+            .resource::<CurrentGame>()
+            .next_queue
+            .preview_count(),
+        previews - 1
+    );
+}
+
+#[test]
+fn randomizer_is_picked_in_settings() {
+    let mut app = menu_app();
+    press(&mut app, KeyCode::ArrowDown);
+    press(&mut app, KeyCode::Enter);
+    assert!(screen_texts(&mut app).contains(&"Randomizer: 7-bag".to_string()));
+
+    // the randomizer is the seventh setting
+    for _ in 0..6 {
+        press(&mut app, KeyCode::ArrowDown);
+    }
+    press(&mut app, KeyCode::ArrowRight);
+    assert_eq!(
+        **app.world().resource::<RandomizerChoice>(),
+        RandomizerKind::FourteenBag
+    );
+    assert!(screen_texts(&mut app).contains(&"Randomizer: 14-bag".to_string()));
+}
+
+#[derive(Resource, Default)]
+struct TestSetting(isize);
+
+#[test]
+fn modes_add_their_own_settings_after_the_general_ones() {
+    let mut app = menu_app();
+    app.init_resource::<TestSetting>();
+    app.register_setting(Setting {
+        label: |world| format!("Test knob: {}", world.resource::<TestSetting>().0),
+        adjust: |world, direction| world.resource_mut::<TestSetting>().0 += direction,
+    });
+    press(&mut app, KeyCode::ArrowDown);
+    press(&mut app, KeyCode::Enter);
+    assert!(screen_texts(&mut app).contains(&"Test knob: 0".to_string()));
+
+    press(&mut app, KeyCode::ArrowUp);
+    press(&mut app, KeyCode::ArrowRight);
+    assert_eq!(app.world().resource::<TestSetting>().0, 1);
+    assert!(screen_texts(&mut app).contains(&"Test knob: 1".to_string()));
+}
+
+#[test]
+fn high_scores_are_kept_in_order_per_mode() {
+    let mut high_scores = HighScores::default();
+    for score in [300, 900, 500] {
+        high_scores.submit(
+            "endless",
+            ScoreEntry {
+                score,
+                level: 1,
+                seed: 0,
+            },
+        );
+    }
+    let scores: Vec<u32> = high_scores
+        .for_mode("endless")
+        .iter()
+        .map(|entry| entry.score)
+        .collect();
+    assert_eq!(scores, vec![900, 500, 300]);
+    assert!(high_scores.for_mode("test").is_empty());
+}
+
+fn test_table(_world: &World, mode: &'static str) -> Vec<String> {
+    vec![format!("  fastest {mode}")]
+}
+
+#[test]
+fn high_scores_show_each_mode_leaderboard() {
+    let mut app = menu_app();
+    app.register_leaderboard(
+        TEST_MODE.id,
+        Leaderboard {
+            ranking: Ranking::Time,
+            render: test_table,
+        },
+    );
+    for _ in 0..2 {
+        press(&mut app, KeyCode::ArrowDown);
+    }
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(menu_state(&app), MenuState::HighScores);
+
+    let texts = screen_texts(&mut app);
+    for line in [
+        "Endless (by score)",
+        "  no scores yet",
+        "Test (by time)",
+        "  fastest test",
+    ] {
+        assert!(texts.iter().any(|text| text == line), "missing {line}");
+    }
+}
+
+#[test]
+fn replay_restarts_a_past_seed() {
+    let mut app = menu_app();
+    app.world_mut()
+        .resource_mut::<ReplayLog>()
+        .record(ReplayEntry {
+            mode: "test",
+            seed: 4242,
+            score: 100,
+        });
+    for _ in 0..3 {
+        press(&mut app, KeyCode::ArrowDown);
+    }
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(menu_state(&app), MenuState::Replays);
+
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(game_state(&app), GameState::Playing);
+    assert_eq!(app.world().resource::<CurrentGame>().rng.seed(), 4242);
+    assert_eq!(app.world().resource::<SelectedMode>().0, "test");
+}
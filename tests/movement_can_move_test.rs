@@ -1,55 +1,76 @@
 use bevy::prelude::*;
-use bevy::state::app::StatesPlugin;
-use tetris_rust_bevy_ver0_16::components::{Piece, Position};
-use tetris_rust_bevy_ver0_16::game_color::GameColor;
-use tetris_rust_bevy_ver0_16::game_constants::NUM_BLOCKS_Y;
-use tetris_rust_bevy_ver0_16::game_types::{GameMap, Presence};
-use tetris_rust_bevy_ver0_16::state::GameState;
-use tetris_rust_bevy_ver0_16::systems::movement::{can_move, move_piece_down};
+use std::time::Duration;
+use tetris_rust_bevy_ver0_16::game_constants::MATRIX_ROWS;
+use tetris_rust_bevy_ver0_16::game_core::board::{GameMap, PieceType};
+use tetris_rust_bevy_ver0_16::game_core::color::GameColor;
+use tetris_rust_bevy_ver0_16::game_core::piece::{Piece, Position};
+use tetris_rust_bevy_ver0_16::game_core::{Action, Game};
+use tetris_rust_bevy_ver0_16::resources::{CurrentGame, LockEvent};
+use tetris_rust_bevy_ver0_16::systems::movement::step_game;
 
 #[test]
-fn can_move_false_at_bottom() {
+fn collides_with_the_floor() {
     let piece = Piece {
         states: [1632, 1632, 1632, 1632],
         color: GameColor::Yellow,
         current_state: 0,
+        piece_type: PieceType::O,
     };
     let pos = Position {
         x: 0,
-        y: (NUM_BLOCKS_Y - 1) as isize,
+        y: (MATRIX_ROWS - 1) as isize,
     };
     let game_map = GameMap::default();
-    assert!(!can_move(&piece, &pos, pos.y + 1, &game_map));
+    assert!(game_map.collides(&piece, piece.current_state, pos.x, pos.y + 1));
 }
 
 #[test]
-fn move_piece_down_stops_and_writes_on_land() {
+fn step_game_locks_a_landed_piece_and_spawns_the_next() {
     let mut app = App::new();
-    app.add_plugins(StatesPlugin);
-    app.init_resource::<GameMap>();
-    app.insert_state(GameState::Playing);
-    app.add_systems(Update, move_piece_down);
+    app.init_resource::<Time>();
+    app.add_event::<LockEvent>();
+    let mut game = Game::new(0);
+    game.lock_delay.delay = 0.0;
+    for _ in 0..game.game_map.height() {
+        game.apply(Action::SoftDrop);
+    }
+    app.insert_resource(CurrentGame(game));
+    app.add_systems(Update, step_game);
 
-    // spawn a simple 1x1-like piece near bottom by using 'O' with position
-    let piece = Piece {
-        states: [1632, 1632, 1632, 1632],
-        color: GameColor::Yellow,
-        current_state: 0,
-    };
-    let pos = Position {
-        x: 0,
-        y: (NUM_BLOCKS_Y - 2) as isize,
+    app.world_mut()
+        .resource_mut::<Time>()
+        .advance_by(Duration::from_millis(16));
+    app.update();
+
+    // something is written on the bottom row and a new piece is in play
+    let game = app.world().resource::<CurrentGame>();
+    assert_ne!(game.game_map.row_mask(MATRIX_ROWS - 1), 0);
+    assert_eq!(game.pieces_placed, 1);
+    assert_eq!(game.spawns(), 2);
+    assert_eq!(app.world().resource::<Events<LockEvent>>().len(), 1);
+}
+
+#[test]
+fn gravity_pulls_the_piece_down_one_row_per_interval() {
+    let mut app = App::new();
+    app.init_resource::<Time>();
+    app.add_event::<LockEvent>();
+    app.insert_resource(CurrentGame(Game::new(0)));
+    app.add_systems(Update, step_game);
+    let row = |app: &App| {
+        let game = app.world().resource::<CurrentGame>();
+        game.active_piece().unwrap().1.y
     };
-    let entity = app.world_mut().spawn((piece, pos)).id();
+    let start = row(&app);
+    let seconds_per_row = app
+        .world()
+        .resource::<CurrentGame>()
+        .gravity
+        .seconds_per_row;
 
-    // first update: should land and despawn
+    app.world_mut()
+        .resource_mut::<Time>()
+        .advance_by(Duration::from_secs_f32(seconds_per_row * 1.5));
     app.update();
-    assert!(app.world().get_entity(entity).is_err());
-
-    // ensure something is written on bottom row
-    let gm = app.world().resource::<GameMap>();
-    let bottom_has_any = gm.0[NUM_BLOCKS_Y - 1]
-        .iter()
-        .any(|p| matches!(p, Presence::Yes(_)));
-    assert!(bottom_has_any);
+    assert_eq!(row(&app), start + 1);
 }
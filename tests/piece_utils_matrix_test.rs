@@ -1,6 +1,6 @@
-use tetris_rust_bevy_ver0_16::game_color::GameColor;
-use tetris_rust_bevy_ver0_16::game_types::Presence;
-use tetris_rust_bevy_ver0_16::piece_utils::get_block_matrix;
+use tetris_rust_bevy_ver0_16::game_core::board::Presence;
+use tetris_rust_bevy_ver0_16::game_core::color::GameColor;
+use tetris_rust_bevy_ver0_16::game_core::piece::get_block_matrix;
 
 #[test]
 fn get_block_matrix_sets_presence_bits() {
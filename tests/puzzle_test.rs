@@ -0,0 +1,399 @@
+use bevy::prelude::*;
+use bevy::state::app::StatesPlugin;
+use std::path::Path;
+use tetris_rust_bevy_ver0_16::game_constants::MATRIX_ROWS;
+use tetris_rust_bevy_ver0_16::game_core::board::{BoardSize, GameMap, PieceType, Presence};
+use tetris_rust_bevy_ver0_16::game_core::piece::{Piece, Position};
+use tetris_rust_bevy_ver0_16::game_core::randomizer::{
+    GameRng, NextQueue, PieceRandomizer, SequenceRandomizer,
+};
+use tetris_rust_bevy_ver0_16::game_core::tspin::{PieceLocked, TSpin};
+use tetris_rust_bevy_ver0_16::game_core::{Action, Game};
+use tetris_rust_bevy_ver0_16::modes::SelectedMode;
+use tetris_rust_bevy_ver0_16::modes::dig::GARBAGE_COLOR;
+use tetris_rust_bevy_ver0_16::modes::puzzle::{
+    PUZZLE, PUZZLE_DIR, Puzzle, PuzzleDir, PuzzleGoal, PuzzleModePlugin, PuzzlePack,
+};
+use tetris_rust_bevy_ver0_16::resources::{CurrentGame, LockEvent};
+use tetris_rust_bevy_ver0_16::state::{GameResults, GameState};
+use tetris_rust_bevy_ver0_16::stats::GameStats;
+use tetris_rust_bevy_ver0_16::systems::game_flow::start_game;
+
+const BOTTOM: usize = MATRIX_ROWS - 1;
+
+const TETRIS: &str = "
+# a comment
+name: Tetris
+goal: lines 4
+queue: I
+board:
+XXXXXXXXX.
+XXXXXXXXX.
+XXXXXXXXX.
+TTTXXXXXX.
+";
+
+#[test]
+fn parses_name_goal_queue_and_board() {
+    let puzzle = Puzzle::parse(TETRIS).unwrap();
+    assert_eq!(puzzle.name, "Tetris");
+    assert_eq!(puzzle.goal, PuzzleGoal::Lines(4));
+    assert_eq!(puzzle.queue, vec![PieceType::I]);
+    assert_eq!(puzzle.board.len(), 4);
+    assert_eq!(puzzle.board[0][0], Presence::Yes(GARBAGE_COLOR));
+    assert_eq!(puzzle.board[0][9], Presence::No);
+    assert_eq!(
+        puzzle.board[3][0],
+        Presence::Yes(Piece::from(PieceType::T).color)
+    );
+}
+
+#[test]
+fn parses_every_goal() {
+    let goal = |text: &str| {
+        Puzzle::parse(&format!("name: a\ngoal: {text}\nqueue: T"))
+            .unwrap()
+            .goal
+    };
+    assert_eq!(goal("perfect clear"), PuzzleGoal::PerfectClear);
+    assert_eq!(goal("tspin single"), PuzzleGoal::TSpin(1));
+    assert_eq!(goal("tspin triple"), PuzzleGoal::TSpin(3));
+    assert_eq!(goal("lines 2"), PuzzleGoal::Lines(2));
+    assert_eq!(PuzzleGoal::TSpin(3).label(), "T-spin triple");
+    assert_eq!(PuzzleGoal::Lines(4).label(), "Clear 4 lines");
+    assert_eq!(PuzzleGoal::TSpin(0).label(), "T-spin zero");
+}
+
+#[test]
+fn reports_bad_files() {
+    let error = |text: &str| Puzzle::parse(text).unwrap_err();
+    assert_eq!(
+        error("name: a\ngoal: win\nqueue: T"),
+        "line 2: unknown goal"
+    );
+    assert_eq!(
+        error("name: a\ngoal: lines 0\nqueue: T"),
+        "line 2: unknown goal"
+    );
+    assert_eq!(
+        error("name: a\ngoal: lines 1\nqueue: TQ"),
+        "line 3: unknown piece in queue"
+    );
+    assert_eq!(error("name: a\ngoal: lines 1"), "the queue has no pieces");
+    assert_eq!(error("goal: lines 1\nqueue: T"), "missing name");
+    assert_eq!(
+        error("name: a\ngoal: lines 1\nqueue: T\nboard:\nXX\nX.X"),
+        "line 6: board rows differ in width"
+    );
+    assert_eq!(
+        error("name: a\ngoal: lines 1\nqueue: T\nboard:\nX?"),
+        "line 5: unknown board cell"
+    );
+}
+
+#[test]
+fn board_sits_at_the_bottom_of_the_map() {
+    let puzzle = Puzzle::parse(TETRIS).unwrap();
+    let mut game_map = GameMap::default();
+    puzzle.load_board(&mut game_map);
+
+    assert_eq!(game_map.row_mask(BOTTOM), 0b01_1111_1111);
+    assert_eq!(game_map.row_mask(BOTTOM - 3), 0b01_1111_1111);
+    assert_eq!(game_map.row_mask(BOTTOM - 4), 0);
+}
+
+#[test]
+fn sequence_randomizer_stops_after_its_list() {
+    let mut randomizer = PieceRandomizer(Box::new(SequenceRandomizer::new(vec![
+        PieceType::T,
+        PieceType::I,
+    ])));
+    let mut rng = GameRng::from_seed(0);
+    let pieces: Vec<_> = (0..3).map(|_| randomizer.next_piece(&mut rng)).collect();
+    assert_eq!(pieces, [Some(PieceType::T), Some(PieceType::I), None]);
+
+    randomizer.reset();
+    assert_eq!(randomizer.next_piece(&mut rng), Some(PieceType::T));
+}
+
+#[test]
+fn next_queue_only_previews_the_pieces_left() {
+    let mut randomizer = PieceRandomizer(Box::new(SequenceRandomizer::new(vec![
+        PieceType::I,
+        PieceType::O,
+        PieceType::I,
+    ])));
+    let mut rng = GameRng::from_seed(0);
+    let mut queue = NextQueue::default();
+    assert_eq!(queue.pop(&mut randomizer, &mut rng), Some(PieceType::I));
+    assert_eq!(
+        queue.pieces().collect::<Vec<_>>(),
+        [PieceType::O, PieceType::I]
+    );
+    queue.pop(&mut randomizer, &mut rng);
+    queue.pop(&mut randomizer, &mut rng);
+    assert_eq!(queue.pieces().count(), 0);
+    assert_eq!(queue.pop(&mut randomizer, &mut rng), None);
+}
+
+#[test]
+fn pack_steps_through_puzzles() {
+    let tetris = Puzzle::parse(TETRIS).unwrap();
+    let mut pack = PuzzlePack::new(vec![tetris.clone(), tetris]);
+    assert_eq!(pack.label(), "1/2 Tetris");
+    pack.step(1);
+    pack.step(1);
+    assert_eq!(pack.label(), "2/2 Tetris");
+    assert_eq!(PuzzlePack::default().label(), "none");
+}
+
+// Plays a puzzle through the headless core and returns every lock.
+fn play(puzzle: &Puzzle, actions: &[Action]) -> Vec<PieceLocked> {
+    let randomizer = PieceRandomizer(Box::new(SequenceRandomizer::new(puzzle.queue.clone())));
+    let mut game = Game::with_randomizer(0, randomizer);
+    puzzle.load_board(&mut game.game_map);
+    actions
+        .iter()
+        .filter_map(|&action| game.apply(action))
+        .collect()
+}
+
+fn bundled(name: &str) -> Puzzle {
+    let mut pack = PuzzlePack::load(Path::new(PUZZLE_DIR));
+    while pack.selected().unwrap().name != name {
+        pack.step(1);
+    }
+    pack.selected().unwrap().clone()
+}
+
+fn solved(puzzle: &Puzzle, locks: &[PieceLocked]) -> bool {
+    let mut lines = 0;
+    locks.iter().take(puzzle.queue.len()).any(|locked| {
+        lines += locked.lines_cleared;
+        puzzle.goal.is_met(locked, lines)
+    })
+}
+
+#[test]
+fn bundled_pack_loads() {
+    let pack = PuzzlePack::load(Path::new(PUZZLE_DIR));
+    assert_eq!(pack.len(), 3);
+}
+
+fn loader_app(dir: PuzzleDir) -> App {
+    let mut app = App::new();
+    app.add_plugins(StatesPlugin);
+    app.insert_resource(CurrentGame(Game::unstarted(
+        0,
+        &BoardSize::default(),
+        PieceRandomizer::default(),
+    )));
+    app.init_resource::<GameStats>();
+    app.add_event::<LockEvent>();
+    app.init_state::<GameState>();
+    app.add_systems(OnEnter(GameState::Playing), start_game);
+    app.insert_resource(dir);
+    app.add_plugins(PuzzleModePlugin);
+    app.insert_resource(SelectedMode(PUZZLE.id));
+    app.update();
+    app
+}
+
+#[test]
+fn pack_loads_at_startup_from_the_puzzle_dir() {
+    let app = loader_app(PuzzleDir::default());
+    assert_eq!(app.world().resource::<PuzzlePack>().len(), 3);
+}
+
+#[test]
+fn a_missing_puzzle_dir_finishes_with_a_hint() {
+    let mut app = loader_app(PuzzleDir("no/such/dir".into()));
+    assert!(app.world().resource::<PuzzlePack>().is_empty());
+
+    app.world_mut()
+        .resource_mut::<NextState<GameState>>()
+        .set(GameState::Playing);
+    app.update();
+    app.update();
+    assert_eq!(state(&app), GameState::Finished);
+    let results = app.world().resource::<GameResults>();
+    assert_eq!(results.headline, "No Puzzles");
+    assert_eq!(results.lines, ["Add .puzzle files to no/such/dir"]);
+}
+
+#[test]
+fn bundled_tetris_is_solvable() {
+    use Action::*;
+    let puzzle = bundled("Tetris");
+    let locks = play(
+        &puzzle,
+        &[
+            RotateClockwise,
+            MoveRight,
+            MoveRight,
+            MoveRight,
+            MoveRight,
+            HardDrop,
+        ],
+    );
+    assert!(solved(&puzzle, &locks));
+}
+
+#[test]
+fn bundled_perfect_clear_is_solvable() {
+    use Action::*;
+    let puzzle = bundled("Three-Piece Perfect Clear");
+    let locks = play(
+        &puzzle,
+        &[
+            MoveRight, HardDrop, MoveRight, MoveRight, MoveRight, MoveRight, HardDrop, MoveRight,
+            HardDrop,
+        ],
+    );
+    assert_eq!(locks.len(), 3);
+    assert!(locks[2].perfect_clear);
+    assert!(solved(&puzzle, &locks));
+}
+
+#[test]
+fn holding_never_deals_past_the_queue() {
+    use Action::*;
+    let puzzle = bundled("Three-Piece Perfect Clear");
+    let randomizer = PieceRandomizer(Box::new(SequenceRandomizer::new(puzzle.queue.clone())));
+    let mut game = Game::with_randomizer(0, randomizer);
+    puzzle.load_board(&mut game.game_map);
+
+    game.apply(Hold);
+    assert_eq!(game.held_piece(), Some(PieceType::I));
+    assert_eq!(game.active_piece().unwrap().0.piece_type, PieceType::O);
+    assert_eq!(game.next_queue.pieces().collect::<Vec<_>>(), [PieceType::I]);
+
+    game.apply(HardDrop);
+    assert_eq!(game.next_queue.pieces().count(), 0);
+    game.apply(Hold);
+    assert_eq!(game.active_piece().unwrap().0.piece_type, PieceType::I);
+    game.apply(HardDrop);
+
+    // the held I is the last piece; there is no fourth one to deal
+    assert_eq!(game.pieces_placed, 2);
+    assert_eq!(game.held_piece(), Some(PieceType::I));
+    assert!(game.active_piece().is_none());
+    assert!(!game.is_game_over());
+    assert!(game.apply(Hold).is_none());
+    assert_eq!(game.pieces_placed, 2);
+}
+
+#[test]
+fn bundled_tspin_triple_is_solvable() {
+    use Action::*;
+    let puzzle = bundled("T-Spin Triple");
+    let mut actions = vec![MoveLeft];
+    actions.extend([SoftDrop; 40]);
+    actions.extend([MoveLeft, RotateClockwise, HardDrop]);
+    let locks = play(&puzzle, &actions);
+    assert_eq!(locks[0].tspin, TSpin::Full);
+    assert_eq!(locks[0].lines_cleared, 3);
+    assert!(solved(&puzzle, &locks));
+}
+
+fn puzzle_app(puzzle: Puzzle) -> App {
+    let mut app = App::new();
+    app.add_plugins(StatesPlugin);
+    app.insert_resource(CurrentGame(Game::unstarted(
+        0,
+        &BoardSize::default(),
+        PieceRandomizer::default(),
+    )));
+    app.init_resource::<GameStats>();
+    app.add_event::<LockEvent>();
+    app.init_state::<GameState>();
+    app.add_systems(OnEnter(GameState::Playing), start_game);
+    app.add_plugins(PuzzleModePlugin);
+    app.insert_resource(SelectedMode(PUZZLE.id));
+    // Startup loads the bundled pack first; the test's puzzle replaces it.
+    app.update();
+    app.insert_resource(PuzzlePack::new(vec![puzzle]));
+    app.world_mut()
+        .resource_mut::<NextState<GameState>>()
+        .set(GameState::Playing);
+    app.update();
+    app
+}
+
+fn lock(app: &mut App, lines_cleared: u32, tspin: TSpin) {
+    {
+        let mut game = app.world_mut().resource_mut::<CurrentGame>();
+        game.pieces_placed += 1;
+        game.lines_cleared += lines_cleared;
+    }
+    app.world_mut().send_event(LockEvent(PieceLocked {
+        piece_type: PieceType::T,
+        position: Position { x: 0, y: 0 },
+        rotation: 1,
+        tspin,
+        lines_cleared,
+        perfect_clear: false,
+    }));
+    app.update();
+    app.update();
+}
+
+fn state(app: &App) -> GameState {
+    *app.world().resource::<State<GameState>>().get()
+}
+
+#[test]
+fn starting_a_puzzle_loads_its_board_and_queue() {
+    let app = puzzle_app(Puzzle::parse(TETRIS).unwrap());
+    let game = app.world().resource::<CurrentGame>();
+    assert!(game.is_started());
+    assert_eq!(game.game_map.row_mask(BOTTOM), 0b01_1111_1111);
+    assert_eq!(game.active_piece().unwrap().0.piece_type, PieceType::I);
+    assert_eq!(game.next_queue.pieces().count(), 0);
+}
+
+#[test]
+fn reaching_the_goal_solves_the_puzzle() {
+    let mut app = puzzle_app(Puzzle::parse(TETRIS).unwrap());
+    lock(&mut app, 4, TSpin::None);
+
+    assert_eq!(state(&app), GameState::Finished);
+    let results = app.world().resource::<GameResults>();
+    assert_eq!(results.headline, "Puzzle Solved");
+    assert_eq!(results.lines[2], "Pieces: 1/1");
+}
+
+#[test]
+fn running_out_of_pieces_fails_the_puzzle() {
+    let puzzle = Puzzle::parse("name: TST\ngoal: tspin triple\nqueue: TT").unwrap();
+    let mut app = puzzle_app(puzzle);
+    lock(&mut app, 2, TSpin::Full);
+    assert_eq!(state(&app), GameState::Playing);
+
+    lock(&mut app, 1, TSpin::Mini);
+    assert_eq!(state(&app), GameState::Finished);
+    assert_eq!(
+        app.world().resource::<GameResults>().headline,
+        "Puzzle Failed"
+    );
+}
+
+#[test]
+fn the_last_piece_stuck_in_hold_fails_the_puzzle() {
+    let puzzle = Puzzle::parse("name: TST\ngoal: tspin triple\nqueue: TT").unwrap();
+    let mut app = puzzle_app(puzzle);
+    app.world_mut()
+        .resource_mut::<CurrentGame>()
+        .apply(Action::Hold);
+    assert_eq!(
+        app.world().resource::<CurrentGame>().held_piece(),
+        Some(PieceType::T)
+    );
+    lock(&mut app, 0, TSpin::None);
+
+    assert_eq!(state(&app), GameState::Finished);
+    assert_eq!(
+        app.world().resource::<GameResults>().headline,
+        "Puzzle Failed"
+    );
+}
@@ -0,0 +1,99 @@
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use tetris_rust_bevy_ver0_16::game_core::board::PieceType;
+use tetris_rust_bevy_ver0_16::game_core::randomizer::{
+    BagRandomizer, HistoryRandomizer, PureRandomizer, Randomizer, RandomizerKind,
+};
+
+fn count(pieces: &[PieceType], piece_type: PieceType) -> usize {
+    pieces.iter().filter(|&&p| p == piece_type).count()
+}
+
+#[test]
+fn seven_bag_deals_every_piece_once_per_bag() {
+    let mut rng = StdRng::seed_from_u64(7);
+    let mut randomizer = BagRandomizer::seven_bag();
+
+    for _ in 0..10 {
+        let bag: Vec<_> = (0..7)
+            .map(|_| randomizer.next_piece(&mut rng).unwrap())
+            .collect();
+        for piece_type in PieceType::ALL {
+            assert_eq!(count(&bag, piece_type), 1);
+        }
+    }
+}
+
+#[test]
+fn fourteen_bag_deals_every_piece_twice_per_bag() {
+    let mut rng = StdRng::seed_from_u64(14);
+    let mut randomizer = BagRandomizer::fourteen_bag();
+
+    for _ in 0..10 {
+        let bag: Vec<_> = (0..14)
+            .map(|_| randomizer.next_piece(&mut rng).unwrap())
+            .collect();
+        for piece_type in PieceType::ALL {
+            assert_eq!(count(&bag, piece_type), 2);
+        }
+    }
+}
+
+#[test]
+fn pure_random_eventually_deals_every_piece() {
+    let mut rng = StdRng::seed_from_u64(1);
+    let mut randomizer = PureRandomizer;
+
+    let pieces: Vec<_> = (0..200)
+        .map(|_| randomizer.next_piece(&mut rng).unwrap())
+        .collect();
+    for piece_type in PieceType::ALL {
+        assert!(count(&pieces, piece_type) > 0);
+    }
+}
+
+#[test]
+fn history_randomizer_never_opens_with_s_z_or_o() {
+    for seed in 0..50 {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut randomizer = HistoryRandomizer::default();
+        let first = randomizer.next_piece(&mut rng).unwrap();
+        assert!(!matches!(first, PieceType::S | PieceType::Z | PieceType::O));
+    }
+}
+
+#[test]
+fn history_randomizer_repeats_less_than_pure_random() {
+    let mut rng = StdRng::seed_from_u64(3);
+    let mut history = HistoryRandomizer::default();
+    let mut pure = PureRandomizer;
+
+    let repeats = |pieces: Vec<PieceType>| pieces.windows(2).filter(|w| w[0] == w[1]).count();
+    let history_repeats = repeats(
+        (0..2000)
+            .map(|_| history.next_piece(&mut rng).unwrap())
+            .collect(),
+    );
+    let pure_repeats = repeats(
+        (0..2000)
+            .map(|_| pure.next_piece(&mut rng).unwrap())
+            .collect(),
+    );
+    assert!(history_repeats < pure_repeats);
+}
+
+#[test]
+fn randomizer_kinds_are_picked_by_name_and_stop_at_the_ends() {
+    for kind in RandomizerKind::ALL {
+        assert_eq!(RandomizerKind::from_name(kind.name()), Some(kind));
+    }
+    assert_eq!(RandomizerKind::from_name("tgm"), None);
+
+    let mut kind = RandomizerKind::default();
+    kind.step(-1);
+    assert_eq!(kind, RandomizerKind::SevenBag);
+    for _ in 0..10 {
+        kind.step(1);
+    }
+    assert_eq!(kind, RandomizerKind::History);
+}
@@ -0,0 +1,116 @@
+use bevy::prelude::*;
+use std::fs;
+use std::time::Duration;
+use tetris_rust_bevy_ver0_16::modes::sprint::SPRINT;
+use tetris_rust_bevy_ver0_16::modes::{ENDLESS, GameModes};
+use tetris_rust_bevy_ver0_16::records::{
+    BestTimes, HighScores, RecordsFile, ScoreEntry, TimedRun, load_records, read_records,
+    save_records, write_records,
+};
+
+fn modes() -> GameModes {
+    let mut modes = GameModes::default();
+    modes.register(ENDLESS);
+    modes.register(SPRINT);
+    modes
+}
+
+fn records() -> (HighScores, BestTimes) {
+    let mut high_scores = HighScores::default();
+    let mut bests = BestTimes::default();
+    for score in [300, 1200, 800] {
+        high_scores.submit(
+            ENDLESS.id,
+            ScoreEntry {
+                score,
+                level: 3,
+                seed: 9,
+            },
+        );
+    }
+    bests.submit(
+        SPRINT.id,
+        40,
+        TimedRun {
+            time: Duration::from_millis(61_250),
+            score: 4000,
+            pieces: 102,
+            seed: 17,
+        },
+    );
+    (high_scores, bests)
+}
+
+#[test]
+fn records_read_back_what_was_written() {
+    let (high_scores, bests) = records();
+    let text = write_records(&high_scores, &bests);
+
+    let mut read_scores = HighScores::default();
+    let mut read_bests = BestTimes::default();
+    read_records(&text, &modes(), &mut read_scores, &mut read_bests);
+    assert_eq!(
+        read_scores.for_mode(ENDLESS.id),
+        high_scores.for_mode(ENDLESS.id)
+    );
+    assert_eq!(read_bests.get(SPRINT.id, 40), bests.get(SPRINT.id, 40));
+}
+
+#[test]
+fn unknown_modes_and_broken_lines_are_skipped() {
+    let text = "score gone 500 2 1\n\
+                score endless 500\n\
+                time sprint forty 1 2 3 4\n\
+                score endless 700 4 2\n";
+    let mut high_scores = HighScores::default();
+    let mut bests = BestTimes::default();
+    read_records(text, &modes(), &mut high_scores, &mut bests);
+    assert_eq!(high_scores.for_mode("gone"), &[]);
+    assert_eq!(
+        high_scores.for_mode(ENDLESS.id),
+        &[ScoreEntry {
+            score: 700,
+            level: 4,
+            seed: 2,
+        }]
+    );
+    assert_eq!(bests.iter().count(), 0);
+}
+
+#[test]
+fn records_are_kept_between_sessions() {
+    let path = std::env::temp_dir()
+        .join(format!("tetris_records_test_{}", std::process::id()))
+        .join("records.txt");
+    let (high_scores, bests) = records();
+
+    let mut app = App::new();
+    app.insert_resource(RecordsFile(Some(path.clone())));
+    app.insert_resource(high_scores);
+    app.insert_resource(bests);
+    app.add_systems(Update, save_records);
+    app.update();
+
+    let mut app = App::new();
+    app.insert_resource(RecordsFile(Some(path.clone())));
+    app.insert_resource(modes());
+    app.init_resource::<HighScores>();
+    app.init_resource::<BestTimes>();
+    app.add_systems(Startup, load_records);
+    app.update();
+    let _ = fs::remove_dir_all(path.parent().unwrap());
+
+    let world = app.world();
+    assert_eq!(
+        world.resource::<HighScores>().for_mode(ENDLESS.id)[0].score,
+        1200
+    );
+    assert_eq!(
+        world
+            .resource::<BestTimes>()
+            .get(SPRINT.id, 40)
+            .unwrap()
+            .pieces,
+        102
+    );
+}
@@ -0,0 +1,94 @@
+use tetris_rust_bevy_ver0_16::game_core::scoring::Score;
+use tetris_rust_bevy_ver0_16::game_core::scoring::{action_points, is_difficult};
+use tetris_rust_bevy_ver0_16::game_core::tspin::TSpin;
+
+#[test]
+fn line_clears_follow_the_guideline_table() {
+    assert_eq!(action_points(1, TSpin::None), 100);
+    assert_eq!(action_points(2, TSpin::None), 300);
+    assert_eq!(action_points(3, TSpin::None), 500);
+    assert_eq!(action_points(4, TSpin::None), 800);
+    assert_eq!(action_points(0, TSpin::Mini), 100);
+    assert_eq!(action_points(1, TSpin::Mini), 200);
+    assert_eq!(action_points(0, TSpin::Full), 400);
+    assert_eq!(action_points(1, TSpin::Full), 800);
+    assert_eq!(action_points(2, TSpin::Full), 1200);
+    assert_eq!(action_points(3, TSpin::Full), 1600);
+}
+
+#[test]
+fn points_scale_with_level() {
+    let mut score = Score::default();
+    assert_eq!(score.award_lock(4, TSpin::None, false, 3), 2400);
+    assert_eq!(score.value, 2400);
+}
+
+#[test]
+fn back_to_back_tetris_earns_half_again() {
+    let mut score = Score::default();
+    assert_eq!(score.award_lock(4, TSpin::None, false, 1), 800);
+    assert!(score.back_to_back);
+    score.award_lock(0, TSpin::None, false, 1);
+    // 1200 for the B2B tetris, combo was broken by the empty lock
+    assert_eq!(score.award_lock(4, TSpin::None, false, 1), 1200);
+}
+
+#[test]
+fn plain_clear_breaks_back_to_back() {
+    let mut score = Score::default();
+    score.award_lock(2, TSpin::Full, false, 1);
+    score.award_lock(0, TSpin::None, false, 1);
+    score.award_lock(1, TSpin::None, false, 1);
+    assert!(!score.back_to_back);
+    score.award_lock(0, TSpin::None, false, 1);
+    assert_eq!(score.award_lock(2, TSpin::Full, false, 1), 1200);
+}
+
+#[test]
+fn zero_line_tspin_keeps_back_to_back() {
+    let mut score = Score::default();
+    score.award_lock(4, TSpin::None, false, 1);
+    score.award_lock(0, TSpin::Full, false, 1);
+    assert!(score.back_to_back);
+}
+
+#[test]
+fn consecutive_clears_build_a_combo() {
+    let mut score = Score::default();
+    assert_eq!(score.award_lock(1, TSpin::None, false, 1), 100);
+    assert_eq!(score.combo, Some(0));
+    assert_eq!(score.award_lock(1, TSpin::None, false, 1), 150);
+    assert_eq!(score.award_lock(1, TSpin::None, false, 2), 400);
+    assert_eq!(score.combo, Some(2));
+    score.award_lock(0, TSpin::None, false, 1);
+    assert_eq!(score.combo, None);
+}
+
+#[test]
+fn perfect_clear_adds_bonus() {
+    let mut score = Score::default();
+    assert_eq!(score.award_lock(1, TSpin::None, true, 1), 900);
+
+    let mut score = Score {
+        back_to_back: true,
+        ..Default::default()
+    };
+    // 1200 for the B2B tetris plus the 3200 B2B perfect clear bonus
+    assert_eq!(score.award_lock(4, TSpin::None, true, 1), 4400);
+}
+
+#[test]
+fn drops_score_per_cell() {
+    let mut score = Score::default();
+    score.award_drop(5, false);
+    score.award_drop(5, true);
+    assert_eq!(score.value, 15);
+}
+
+#[test]
+fn difficult_clears_are_tetrises_and_line_clearing_spins() {
+    assert!(is_difficult(4, TSpin::None));
+    assert!(is_difficult(1, TSpin::Mini));
+    assert!(!is_difficult(0, TSpin::Full));
+    assert!(!is_difficult(3, TSpin::None));
+}
@@ -1,28 +1,74 @@
-use bevy::prelude::*;
-use bevy::state::app::StatesPlugin;
-use tetris_rust_bevy_ver0_16::game_types::GameMap;
-use tetris_rust_bevy_ver0_16::state::GameState;
-use tetris_rust_bevy_ver0_16::systems::spawning::spawn_piece;
+use tetris_rust_bevy_ver0_16::game_core::board::{BoardSize, GameMap, PieceType};
+use tetris_rust_bevy_ver0_16::game_core::randomizer::{NextQueue, PieceRandomizer};
+use tetris_rust_bevy_ver0_16::game_core::{Action, Game, spawn_position};
+
+fn active_type(game: &Game) -> PieceType {
+    game.active_piece().unwrap().0.piece_type
+}
+
+// Drops each piece and wipes the board so the stack never tops out.
+fn next_piece(game: &mut Game) -> PieceType {
+    game.apply(Action::HardDrop);
+    game.game_map = GameMap::default();
+    active_type(game)
+}
+
+fn piece_sequence(seed: u64, count: usize) -> Vec<PieceType> {
+    let mut game = Game::new(seed);
+    let mut pieces = vec![active_type(&game)];
+    pieces.extend((1..count).map(|_| next_piece(&mut game)));
+    pieces
+}
+
+#[test]
+fn starting_spawns_the_first_piece_at_the_top() {
+    let mut game = Game::unstarted(0, &BoardSize::default(), PieceRandomizer::default());
+    assert!(game.active_piece().is_none());
+    assert_eq!(game.spawns(), 0);
+
+    game.start();
+
+    let (piece, position) = game.active_piece().unwrap();
+    assert_eq!(*position, spawn_position(&game.game_map, piece));
+    assert_eq!(game.spawns(), 1);
+    assert!(!game.is_game_over());
+}
+
+#[test]
+fn same_seed_spawns_same_sequence() {
+    let first = piece_sequence(42, 21);
+    let second = piece_sequence(42, 21);
+    assert_eq!(first, second);
+
+    // the default 7-bag deals every piece in each group of seven
+    for bag in first.chunks(7) {
+        for piece_type in PieceType::ALL {
+            assert!(bag.contains(&piece_type));
+        }
+    }
+}
+
+#[test]
+fn different_seeds_spawn_different_sequences() {
+    assert_ne!(piece_sequence(1, 14), piece_sequence(2, 14));
+}
+
+#[test]
+fn next_queue_previews_the_upcoming_spawns() {
+    let mut game = Game::unstarted(9, &BoardSize::default(), PieceRandomizer::default());
+    game.next_queue = NextQueue::new(3);
+    game.start();
+
+    let previews: Vec<_> = game.next_queue.pieces().collect();
+    assert_eq!(previews.len(), 3);
+
+    for expected in previews {
+        assert_eq!(next_piece(&mut game), expected);
+    }
+}
 
 #[test]
-fn spawn_piece_adds_entity_or_sets_game_over() {
-    let mut app = App::new();
-    app.add_plugins(StatesPlugin);
-    app.init_resource::<GameMap>();
-    app.insert_state(GameState::Playing);
-
-    let before = app.world().entities().len();
-
-    // Use a Commands proxy via system to call spawn_piece
-    app.add_systems(
-        Startup,
-        |mut commands: Commands, game_map: Res<GameMap>, mut next: ResMut<NextState<GameState>>| {
-            spawn_piece(&mut commands, &game_map, &mut next);
-        },
-    );
-
-    app.update();
-
-    let after = app.world().entities().len();
-    assert!(after >= before);
+fn next_queue_preview_count_is_clamped() {
+    assert_eq!(NextQueue::new(0).preview_count(), 1);
+    assert_eq!(NextQueue::new(10).preview_count(), 6);
 }
@@ -0,0 +1,253 @@
+use bevy::input::ButtonInput;
+use bevy::input::keyboard::KeyCode;
+use bevy::prelude::*;
+use bevy::state::app::StatesPlugin;
+use std::time::Duration;
+use tetris_rust_bevy_ver0_16::finesse::{PieceInputs, judge_finesse, minimum_inputs};
+use tetris_rust_bevy_ver0_16::game_core::board::{BoardSize, GameMap, PieceType};
+use tetris_rust_bevy_ver0_16::game_core::piece::{Piece, Position};
+use tetris_rust_bevy_ver0_16::game_core::randomizer::{PieceRandomizer, SequenceRandomizer};
+use tetris_rust_bevy_ver0_16::game_core::tspin::{PieceLocked, TSpin};
+use tetris_rust_bevy_ver0_16::game_core::{Game, spawn_position};
+use tetris_rust_bevy_ver0_16::modes::SelectedMode;
+use tetris_rust_bevy_ver0_16::modes::sprint::{SPRINT, SprintGoal, SprintModePlugin};
+use tetris_rust_bevy_ver0_16::records::{BestTimes, TimedRun};
+use tetris_rust_bevy_ver0_16::resources::{CurrentGame, LockEvent};
+use tetris_rust_bevy_ver0_16::state::{GameResults, GameState};
+use tetris_rust_bevy_ver0_16::stats::{GameClock, GameStats, count_keys, tick_clock};
+
+fn minimum(piece_type: PieceType, x: isize, rotation: usize) -> Option<u32> {
+    minimum_inputs(
+        &GameMap::default(),
+        piece_type,
+        &Position { x, y: 0 },
+        rotation,
+    )
+}
+
+fn spawn_x(piece_type: PieceType) -> isize {
+    spawn_position(&GameMap::default(), &Piece::from(piece_type)).x
+}
+
+#[test]
+fn finesse_counts_taps_and_wall_charges() {
+    let x = spawn_x(PieceType::T);
+    assert_eq!(minimum(PieceType::T, x, 0), Some(0));
+    assert_eq!(minimum(PieceType::T, x - 1, 0), Some(1));
+    // charging into either wall is a single input
+    assert_eq!(minimum(PieceType::T, 0, 0), Some(1));
+    assert_eq!(minimum(PieceType::T, 7, 0), Some(1));
+    // one column in from the wall: charge, then tap back
+    assert_eq!(minimum(PieceType::T, 1, 0), Some(2));
+    assert_eq!(minimum(PieceType::T, x, 2), Some(2));
+}
+
+#[test]
+fn finesse_treats_matching_rotations_alike() {
+    let x = spawn_x(PieceType::I);
+    // the I's two vertical states cover the same cells one column apart
+    assert_eq!(minimum(PieceType::I, x, 1), Some(1));
+    assert_eq!(minimum(PieceType::I, x + 1, 3), Some(1));
+    assert_eq!(minimum(PieceType::O, spawn_x(PieceType::O), 2), Some(0));
+}
+
+fn locked(piece_type: PieceType, x: isize, rotation: usize) -> LockEvent {
+    LockEvent(PieceLocked {
+        piece_type,
+        position: Position { x, y: 30 },
+        rotation,
+        tspin: TSpin::None,
+        lines_cleared: 0,
+        perfect_clear: false,
+    })
+}
+
+fn finesse_app() -> App {
+    let mut app = App::new();
+    let randomizer = PieceRandomizer(Box::new(SequenceRandomizer::new(vec![PieceType::T])));
+    app.insert_resource(CurrentGame(Game::with_randomizer(0, randomizer)));
+    app.init_resource::<GameStats>();
+    app.init_resource::<PieceInputs>();
+    app.add_event::<LockEvent>();
+    app.insert_resource(ButtonInput::<KeyCode>::default());
+    app.add_systems(Update, judge_finesse);
+    app.update();
+    app
+}
+
+fn tap(app: &mut App, key: KeyCode) {
+    {
+        let mut input = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+        input.release_all();
+        input.clear();
+        input.press(key);
+    }
+    app.update();
+    app.world_mut()
+        .resource_mut::<ButtonInput<KeyCode>>()
+        .clear();
+}
+
+#[test]
+fn extra_inputs_are_a_finesse_fault() {
+    let mut app = finesse_app();
+    let x = spawn_x(PieceType::T);
+    tap(&mut app, KeyCode::ArrowLeft);
+    tap(&mut app, KeyCode::ArrowLeft);
+    tap(&mut app, KeyCode::ArrowRight);
+    app.world_mut().send_event(locked(PieceType::T, x - 1, 0));
+    app.update();
+    assert_eq!(app.world().resource::<GameStats>().finesse_faults, 1);
+}
+
+#[test]
+fn clean_and_soft_dropped_pieces_are_not_faults() {
+    let mut app = finesse_app();
+    let x = spawn_x(PieceType::T);
+    tap(&mut app, KeyCode::ArrowLeft);
+    app.world_mut().send_event(locked(PieceType::T, x - 1, 0));
+    app.update();
+
+    tap(&mut app, KeyCode::ArrowLeft);
+    tap(&mut app, KeyCode::ArrowDown);
+    tap(&mut app, KeyCode::ArrowRight);
+    app.world_mut().send_event(locked(PieceType::T, x, 0));
+    app.update();
+    assert_eq!(app.world().resource::<GameStats>().finesse_faults, 0);
+}
+
+#[test]
+fn clock_waits_for_the_first_key() {
+    let mut app = App::new();
+    app.add_plugins(bevy::time::TimePlugin);
+    app.init_resource::<GameClock>();
+    app.insert_resource(ButtonInput::<KeyCode>::default());
+    app.add_systems(Update, tick_clock);
+    app.update();
+    app.update();
+    assert!(!app.world().resource::<GameClock>().started);
+    assert_eq!(app.world().resource::<GameClock>().elapsed, Duration::ZERO);
+
+    // pausing isn't a move
+    app.world_mut()
+        .resource_mut::<ButtonInput<KeyCode>>()
+        .press(KeyCode::Escape);
+    app.update();
+    assert!(!app.world().resource::<GameClock>().started);
+
+    app.world_mut()
+        .resource_mut::<ButtonInput<KeyCode>>()
+        .press(KeyCode::ArrowLeft);
+    app.update();
+    assert!(app.world().resource::<GameClock>().started);
+}
+
+#[test]
+fn sprint_goal_steps_through_the_presets() {
+    let mut goal = SprintGoal::default();
+    assert_eq!(goal.0, 40);
+    goal.step(1);
+    assert_eq!(goal.0, 100);
+    goal.step(1);
+    assert_eq!(goal.0, 100);
+    goal.step(-2);
+    assert_eq!(goal.0, 20);
+}
+
+#[test]
+fn only_faster_times_replace_a_personal_best() {
+    let mut bests = BestTimes::default();
+    let run = |secs| TimedRun {
+        time: Duration::from_secs(secs),
+        score: 0,
+        pieces: 100,
+        seed: 0,
+    };
+    assert!(bests.submit(SPRINT.id, 40, run(60)));
+    assert!(!bests.submit(SPRINT.id, 40, run(61)));
+    assert!(bests.submit(SPRINT.id, 40, run(55)));
+    // each goal and each mode keeps its own best
+    assert!(bests.submit(SPRINT.id, 20, run(90)));
+    assert!(bests.submit("marathon", 40, run(70)));
+    assert_eq!(
+        bests.get(SPRINT.id, 40).unwrap().time,
+        Duration::from_secs(55)
+    );
+}
+
+fn sprint_app(goal: u32) -> App {
+    let mut app = App::new();
+    app.add_plugins(StatesPlugin);
+    app.init_resource::<GameStats>();
+    app.init_resource::<GameClock>();
+    app.insert_resource(CurrentGame(Game::unstarted(
+        5,
+        &BoardSize::default(),
+        PieceRandomizer::default(),
+    )));
+    app.init_state::<GameState>();
+    app.add_event::<LockEvent>();
+    app.add_systems(Update, (count_keys, judge_finesse));
+    app.init_resource::<PieceInputs>();
+    app.insert_resource(ButtonInput::<KeyCode>::default());
+    app.add_plugins(SprintModePlugin);
+    app.insert_resource(SprintGoal(goal));
+    app.insert_resource(SelectedMode(SPRINT.id));
+    app.world_mut()
+        .resource_mut::<NextState<GameState>>()
+        .set(GameState::Playing);
+    app.update();
+    app
+}
+
+fn clear_lines(app: &mut App, lines: u32) {
+    {
+        let mut game = app.world_mut().resource_mut::<CurrentGame>();
+        game.pieces_placed += 1;
+        game.lines_cleared += lines;
+    }
+    app.world_mut().send_event(LockEvent(PieceLocked {
+        lines_cleared: lines,
+        ..*locked(PieceType::I, 3, 0)
+    }));
+    app.update();
+    app.update();
+}
+
+fn state(app: &App) -> GameState {
+    *app.world().resource::<State<GameState>>().get()
+}
+
+#[test]
+fn sprint_finishes_at_the_line_goal() {
+    let mut app = sprint_app(20);
+    app.world_mut().resource_mut::<GameClock>().elapsed = Duration::from_millis(42_500);
+    for _ in 0..4 {
+        clear_lines(&mut app, 4);
+    }
+    assert_eq!(state(&app), GameState::Playing);
+
+    clear_lines(&mut app, 4);
+    assert_eq!(state(&app), GameState::Finished);
+    let results = app.world().resource::<GameResults>();
+    assert_eq!(results.headline, "20 Lines");
+    assert!(results.lines.contains(&"Time: 0:42.50".to_string()));
+    assert!(results.lines.contains(&"New personal best!".to_string()));
+    let best = app
+        .world()
+        .resource::<BestTimes>()
+        .get(SPRINT.id, 20)
+        .unwrap();
+    assert_eq!(best.time, Duration::from_millis(42_500));
+    assert_eq!(best.seed, 5);
+}
+
+#[test]
+fn sprint_ignores_other_modes() {
+    let mut app = sprint_app(20);
+    app.insert_resource(SelectedMode("endless"));
+    for _ in 0..6 {
+        clear_lines(&mut app, 4);
+    }
+    assert_eq!(state(&app), GameState::Playing);
+}
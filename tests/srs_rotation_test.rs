@@ -0,0 +1,109 @@
+use tetris_rust_bevy_ver0_16::game_constants::NUM_BLOCKS_X;
+use tetris_rust_bevy_ver0_16::game_core::board::{GameMap, PieceType, Presence};
+use tetris_rust_bevy_ver0_16::game_core::color::GameColor;
+use tetris_rust_bevy_ver0_16::game_core::piece::{Piece, Position};
+use tetris_rust_bevy_ver0_16::game_core::srs::{Rotation, try_rotate};
+
+#[test]
+fn rotation_in_open_space_uses_first_test() {
+    let game_map = GameMap::default();
+    let piece = Piece::from(PieceType::T);
+    let pos = Position { x: 4, y: 5 };
+
+    let result = try_rotate(&piece, &pos, Rotation::Clockwise, &game_map).unwrap();
+    assert_eq!(result.state, 1);
+    assert_eq!(result.position, pos);
+    assert_eq!(result.kick_index, 0);
+
+    let result = try_rotate(&piece, &pos, Rotation::CounterClockwise, &game_map).unwrap();
+    assert_eq!(result.state, 3);
+}
+
+#[test]
+fn t_piece_kicks_off_left_wall() {
+    let game_map = GameMap::default();
+    // state R hugging the left wall: the stem sits in column 0
+    let piece = Piece {
+        current_state: 1,
+        ..Piece::from(PieceType::T)
+    };
+    let pos = Position { x: -1, y: 5 };
+
+    let result = try_rotate(&piece, &pos, Rotation::Clockwise, &game_map).unwrap();
+    assert_eq!(result.state, 2);
+    assert_eq!(result.position, Position { x: 0, y: 5 });
+    assert_eq!(result.kick_index, 1);
+}
+
+#[test]
+fn i_piece_kicks_off_right_wall() {
+    let game_map = GameMap::default();
+    // vertical I in the last column
+    let piece = Piece {
+        current_state: 1,
+        ..Piece::from(PieceType::I)
+    };
+    let pos = Position {
+        x: NUM_BLOCKS_X as isize - 3,
+        y: 5,
+    };
+
+    let result = try_rotate(&piece, &pos, Rotation::Clockwise, &game_map).unwrap();
+    assert_eq!(result.state, 2);
+    assert_eq!(
+        result.position,
+        Position {
+            x: NUM_BLOCKS_X as isize - 4,
+            y: 5
+        }
+    );
+    assert_eq!(result.kick_index, 1);
+}
+
+#[test]
+fn o_piece_never_moves() {
+    let mut game_map = GameMap::default();
+    game_map.set(3, 3, Presence::Yes(GameColor::Gray));
+    let piece = Piece::from(PieceType::O);
+    let pos = Position { x: 3, y: 2 };
+
+    let result = try_rotate(&piece, &pos, Rotation::Clockwise, &game_map).unwrap();
+    assert_eq!(result.position, pos);
+    assert_eq!(result.kick_index, 0);
+
+    let blocked = Position { x: 2, y: 2 };
+    assert!(try_rotate(&piece, &blocked, Rotation::Clockwise, &game_map).is_none());
+}
+
+#[test]
+fn rotation_fails_when_every_test_collides() {
+    let mut game_map = GameMap::default();
+    for y in 0..game_map.height() {
+        for x in 0..game_map.width() {
+            if y != 6 || !(3..7).contains(&x) {
+                game_map.set(x, y, Presence::Yes(GameColor::Gray));
+            }
+        }
+    }
+    // flat I buried in a one-row tunnel with no room to stand up
+    let piece = Piece::from(PieceType::I);
+    let pos = Position { x: 3, y: 5 };
+
+    assert!(try_rotate(&piece, &pos, Rotation::Clockwise, &game_map).is_none());
+}
+
+#[test]
+fn rotation_can_kick_into_rows_above_the_board() {
+    let mut game_map = GameMap::default();
+    for y in 3..game_map.height() {
+        for x in 0..game_map.width() {
+            game_map.set(x, y, Presence::Yes(GameColor::Gray));
+        }
+    }
+    // flat I resting on the stack stands up into the hidden rows
+    let piece = Piece::from(PieceType::I);
+    let pos = Position { x: 3, y: 1 };
+
+    let result = try_rotate(&piece, &pos, Rotation::Clockwise, &game_map).unwrap();
+    assert!(result.position.y < 0);
+}
@@ -0,0 +1,108 @@
+use bevy::prelude::*;
+use std::time::Duration;
+use tetris_rust_bevy_ver0_16::game_core::Game;
+use tetris_rust_bevy_ver0_16::game_core::board::{GameMap, PieceType};
+use tetris_rust_bevy_ver0_16::game_core::color::GameColor;
+use tetris_rust_bevy_ver0_16::game_core::piece::{Piece, Position};
+use tetris_rust_bevy_ver0_16::game_core::tspin::{PieceLocked, TSpin};
+use tetris_rust_bevy_ver0_16::resources::{CurrentGame, LockEvent};
+use tetris_rust_bevy_ver0_16::stack_display::{StackDisplay, StackReveal, reveal_stack};
+
+fn secs(seconds: f32) -> Duration {
+    Duration::from_secs_f32(seconds)
+}
+
+#[test]
+fn fading_blocks_stay_solid_then_fade_out() {
+    let display = StackDisplay::Fading(5);
+    let locked_at = secs(10.0);
+
+    assert_eq!(display.alpha(locked_at, secs(10.0)), 1.0);
+    assert_eq!(display.alpha(locked_at, secs(15.0)), 1.0);
+    assert!((display.alpha(locked_at, secs(15.5)) - 0.5).abs() < 1e-4);
+    assert_eq!(display.alpha(locked_at, secs(16.0)), 0.0);
+    assert_eq!(display.alpha(locked_at, secs(60.0)), 0.0);
+}
+
+#[test]
+fn visible_and_invisible_ignore_age() {
+    assert_eq!(StackDisplay::Visible.alpha(secs(0.0), secs(100.0)), 1.0);
+    assert_eq!(StackDisplay::Invisible.alpha(secs(1.0), secs(1.0)), 0.0);
+}
+
+#[test]
+fn step_walks_the_display_list() {
+    let mut display = StackDisplay::default();
+    display.step(-1);
+    assert_eq!(display, StackDisplay::Visible);
+    display.step(1);
+    assert_eq!(display, StackDisplay::Fading(10));
+    for _ in 0..10 {
+        display.step(1);
+    }
+    assert_eq!(display, StackDisplay::Invisible);
+    assert_eq!(display.label(), "invisible");
+}
+
+#[test]
+fn stamps_follow_rows_through_clears_and_garbage() {
+    let mut game_map = GameMap::with_buffer(4, 4, 0);
+    let mask = Piece::from(PieceType::I).states[0];
+    // the flat I sits in the second row of its box
+    game_map.place(mask, 0, 1, GameColor::Cyan);
+    game_map.stamp(mask, 0, 1, secs(3.0));
+    game_map.place(mask, 0, 0, GameColor::Cyan);
+    game_map.stamp(mask, 0, 0, secs(7.0));
+    assert_eq!(game_map.locked_at(2, 1), secs(7.0));
+    assert_eq!(game_map.locked_at(2, 2), secs(3.0));
+
+    game_map.clear_rows(&[2]);
+    assert_eq!(game_map.locked_at(2, 2), secs(7.0));
+    assert_eq!(game_map.locked_at(2, 1), Duration::ZERO);
+
+    game_map.insert_bottom_rows(&[game_map.row_with_hole(0)], GameColor::DarkGray);
+    assert_eq!(game_map.locked_at(2, 1), secs(7.0));
+    assert_eq!(game_map.locked_at(2, 3), Duration::ZERO);
+}
+
+fn stack_app(now: f32) -> App {
+    let mut app = App::new();
+    let mut game = Game::new(0);
+    game.tick(now);
+    app.insert_resource(CurrentGame(game));
+    app.init_resource::<StackReveal>();
+    app.add_event::<LockEvent>();
+    app.add_systems(Update, reveal_stack);
+    app
+}
+
+fn locked(lines_cleared: u32) -> LockEvent {
+    LockEvent(PieceLocked {
+        piece_type: PieceType::O,
+        position: Position { x: 3, y: 17 },
+        rotation: 0,
+        tspin: TSpin::None,
+        lines_cleared,
+        perfect_clear: false,
+    })
+}
+
+#[test]
+fn locks_without_clears_keep_the_stack_hidden() {
+    let mut app = stack_app(4.0);
+    app.world_mut().send_event(locked(0));
+    app.update();
+
+    assert!(!app.world().resource::<StackReveal>().is_showing(secs(4.0)));
+}
+
+#[test]
+fn line_clears_reveal_the_stack_briefly() {
+    let mut app = stack_app(4.0);
+    app.world_mut().send_event(locked(1));
+    app.update();
+
+    let reveal = app.world().resource::<StackReveal>();
+    assert!(reveal.is_showing(secs(4.5)));
+    assert!(!reveal.is_showing(secs(5.0)));
+}
@@ -0,0 +1,156 @@
+use tetris_rust_bevy_ver0_16::game_constants::{MATRIX_ROWS, NUM_BLOCKS_X};
+use tetris_rust_bevy_ver0_16::game_core::board::{GameMap, PieceType, Presence};
+use tetris_rust_bevy_ver0_16::game_core::color::GameColor;
+use tetris_rust_bevy_ver0_16::game_core::piece::{Piece, Position};
+use tetris_rust_bevy_ver0_16::game_core::tspin::{PieceLocked, TSpin, detect_tspin};
+use tetris_rust_bevy_ver0_16::game_core::{Action, Game};
+
+const BOTTOM: usize = MATRIX_ROWS - 1;
+
+fn fill(game_map: &mut GameMap, cells: &[(usize, usize)]) {
+    for &(x, y) in cells {
+        game_map.set(x, y, Presence::Yes(GameColor::Gray));
+    }
+}
+
+// A T-spin double slot: two full rows with a T-shaped hole at columns 3-5
+// and an overhang covering column 3 above it.
+fn tsd_board() -> GameMap {
+    let mut game_map = GameMap::default();
+    for x in 0..NUM_BLOCKS_X {
+        if x != 4 {
+            fill(&mut game_map, &[(x, BOTTOM)]);
+        }
+        if !(3..=5).contains(&x) {
+            fill(&mut game_map, &[(x, BOTTOM - 1)]);
+        }
+    }
+    fill(&mut game_map, &[(3, BOTTOM - 2)]);
+    game_map
+}
+
+fn t_pointing_down() -> (Piece, Position) {
+    let piece = Piece {
+        current_state: 2,
+        ..Piece::from(PieceType::T)
+    };
+    // state 2 uses rows 1-2 of its box
+    let position = Position {
+        x: 3,
+        y: BOTTOM as isize - 2,
+    };
+    (piece, position)
+}
+
+#[test]
+fn three_corners_with_both_front_corners_is_full_tspin() {
+    let game_map = tsd_board();
+    let (piece, position) = t_pointing_down();
+    assert_eq!(
+        detect_tspin(&piece, &position, &game_map, Some(0)),
+        TSpin::Full
+    );
+}
+
+#[test]
+fn no_rotation_means_no_tspin() {
+    let game_map = tsd_board();
+    let (piece, position) = t_pointing_down();
+    assert_eq!(
+        detect_tspin(&piece, &position, &game_map, None),
+        TSpin::None
+    );
+}
+
+#[test]
+fn only_other_pieces_can_not_tspin() {
+    let game_map = tsd_board();
+    let (piece, position) = t_pointing_down();
+    let piece = Piece {
+        piece_type: PieceType::L,
+        ..piece
+    };
+    assert_eq!(
+        detect_tspin(&piece, &position, &game_map, Some(0)),
+        TSpin::None
+    );
+}
+
+#[test]
+fn missing_front_corner_is_mini_unless_fifth_kick() {
+    let mut game_map = GameMap::default();
+    // T pointing up on the floor: both back corners are floor, one front
+    // corner is filled and the other is open.
+    fill(&mut game_map, &[(0, BOTTOM - 1)]);
+    let piece = Piece::from(PieceType::T);
+    let position = Position {
+        x: 0,
+        y: BOTTOM as isize - 1,
+    };
+    assert_eq!(
+        detect_tspin(&piece, &position, &game_map, Some(1)),
+        TSpin::Mini
+    );
+    assert_eq!(
+        detect_tspin(&piece, &position, &game_map, Some(4)),
+        TSpin::Full
+    );
+
+    fill(&mut game_map, &[(2, BOTTOM - 1)]);
+    assert_eq!(
+        detect_tspin(&piece, &position, &game_map, Some(1)),
+        TSpin::Full
+    );
+}
+
+#[test]
+fn two_corners_is_not_a_tspin() {
+    let game_map = GameMap::default();
+    let piece = Piece::from(PieceType::T);
+    let position = Position {
+        x: 3,
+        y: BOTTOM as isize - 1,
+    };
+    assert_eq!(
+        detect_tspin(&piece, &position, &game_map, Some(0)),
+        TSpin::None
+    );
+}
+
+// Steers a T into the slot of `tsd_board` and finishes with `last`.
+fn play_tsd(last: &[Action]) -> PieceLocked {
+    let mut game = Game::new(0);
+    game.game_map = tsd_board();
+    game.spawn(PieceType::T);
+    game.apply(Action::RotateClockwise);
+    for _ in 0..game.game_map.height() {
+        game.apply(Action::SoftDrop);
+    }
+    last.iter()
+        .find_map(|&action| game.apply(action))
+        .expect("the T locks")
+}
+
+#[test]
+fn only_a_rotation_as_the_last_move_scores_a_tspin() {
+    use Action::*;
+    let spun = play_tsd(&[RotateClockwise, HardDrop]);
+    assert_eq!(spun.tspin, TSpin::Full);
+    assert_eq!(spun.lines_cleared, 2);
+
+    // blocked shifts are not moves, so the spin still counts
+    let blocked = play_tsd(&[RotateClockwise, MoveRight, MoveLeft, HardDrop]);
+    assert_eq!(blocked.tspin, TSpin::Full);
+
+    // the drop was the last move
+    let dropped = play_tsd(&[HardDrop]);
+    assert_eq!(dropped.tspin, TSpin::None);
+}
+
+#[test]
+fn labels_name_every_variant() {
+    assert_eq!(TSpin::None.label(2), None);
+    assert_eq!(TSpin::Full.label(0).as_deref(), Some("T-Spin"));
+    assert_eq!(TSpin::Full.label(3).as_deref(), Some("T-Spin Triple"));
+    assert_eq!(TSpin::Mini.label(1).as_deref(), Some("Mini T-Spin Single"));
+}
@@ -0,0 +1,129 @@
+use bevy::prelude::*;
+use bevy::state::app::StatesPlugin;
+use std::time::Duration;
+use tetris_rust_bevy_ver0_16::game_core::Game;
+use tetris_rust_bevy_ver0_16::game_core::board::BoardSize;
+use tetris_rust_bevy_ver0_16::game_core::gravity::seconds_per_row;
+use tetris_rust_bevy_ver0_16::game_core::randomizer::PieceRandomizer;
+use tetris_rust_bevy_ver0_16::modes::ultra::{ULTRA, UltraModePlugin, UltraSettings};
+use tetris_rust_bevy_ver0_16::modes::{ENDLESS, EndlessModePlugin, SelectedMode};
+use tetris_rust_bevy_ver0_16::records::{HighScores, ScoreEntry};
+use tetris_rust_bevy_ver0_16::resources::CurrentGame;
+use tetris_rust_bevy_ver0_16::state::{GameResults, GameState};
+use tetris_rust_bevy_ver0_16::stats::{GameClock, GameStats};
+use tetris_rust_bevy_ver0_16::systems::game_flow::start_game;
+
+fn ultra_app(settings: UltraSettings) -> App {
+    mode_app(settings, ULTRA.id)
+}
+
+fn mode_app(settings: UltraSettings, mode: &'static str) -> App {
+    let mut app = App::new();
+    app.add_plugins(StatesPlugin);
+    app.init_resource::<GameClock>();
+    app.init_resource::<GameStats>();
+    app.insert_resource(CurrentGame(Game::unstarted(
+        0,
+        &BoardSize::default(),
+        PieceRandomizer::default(),
+    )));
+    app.init_state::<GameState>();
+    app.add_systems(OnEnter(GameState::Playing), start_game);
+    app.add_plugins((EndlessModePlugin, UltraModePlugin));
+    app.insert_resource(settings);
+    app.insert_resource(SelectedMode(mode));
+    app.world_mut()
+        .resource_mut::<NextState<GameState>>()
+        .set(GameState::Playing);
+    app.update();
+    app
+}
+
+fn state(app: &App) -> GameState {
+    *app.world().resource::<State<GameState>>().get()
+}
+
+fn seconds_per_row_after_level_up(app: &mut App) -> f32 {
+    let mut game = app.world_mut().resource_mut::<CurrentGame>();
+    game.gravity.follow_level(9);
+    game.gravity.seconds_per_row
+}
+
+#[test]
+fn gravity_stays_at_the_chosen_level() {
+    let mut app = ultra_app(UltraSettings {
+        seconds: 120,
+        level: 5,
+    });
+    let game = app.world().resource::<CurrentGame>();
+    assert!(game.is_started());
+    assert_eq!(game.gravity.seconds_per_row, seconds_per_row(5));
+    assert_eq!(seconds_per_row_after_level_up(&mut app), seconds_per_row(5));
+}
+
+#[test]
+fn other_modes_still_follow_the_level() {
+    let mut app = mode_app(UltraSettings::default(), ENDLESS.id);
+    assert_eq!(seconds_per_row_after_level_up(&mut app), seconds_per_row(9));
+}
+
+#[test]
+fn game_ends_when_time_runs_out() {
+    let mut app = ultra_app(UltraSettings {
+        seconds: 60,
+        level: 1,
+    });
+    app.world_mut().resource_mut::<HighScores>().submit(
+        ULTRA.id,
+        ScoreEntry {
+            score: 9000,
+            level: 1,
+            seed: 0,
+        },
+    );
+    app.world_mut().resource_mut::<CurrentGame>().score.value = 4200;
+    app.world_mut().resource_mut::<GameClock>().elapsed = Duration::from_millis(59_990);
+    app.update();
+    assert_eq!(state(&app), GameState::Playing);
+
+    app.world_mut().resource_mut::<GameClock>().elapsed = Duration::from_secs(60);
+    app.update();
+    app.update();
+    assert_eq!(state(&app), GameState::Finished);
+    let results = app.world().resource::<GameResults>();
+    assert_eq!(results.headline, "Time's Up");
+    assert!(results.lines.contains(&"Score: 4200".to_string()));
+    assert!(results.lines.contains(&"Rank 2".to_string()));
+}
+
+#[test]
+fn settings_step_within_their_range() {
+    let mut settings = UltraSettings::default();
+    assert_eq!(settings.duration(), Duration::from_secs(120));
+    settings.step_duration(-1);
+    settings.step_duration(-1);
+    assert_eq!(settings.seconds, 60);
+    settings.step_duration(5);
+    assert_eq!(settings.seconds, 300);
+    settings.step_level(-1);
+    assert_eq!(settings.level, 1);
+    settings.step_level(30);
+    assert_eq!(settings.level, 20);
+}
+
+#[test]
+fn rank_of_matches_where_submit_would_place_a_score() {
+    let mut high_scores = HighScores::default();
+    for score in [300, 100, 200] {
+        high_scores.submit(
+            ULTRA.id,
+            ScoreEntry {
+                score,
+                level: 1,
+                seed: 0,
+            },
+        );
+    }
+    assert_eq!(high_scores.rank_of(ULTRA.id, 250), Some(1));
+    assert_eq!(high_scores.rank_of("sprint", 0), Some(0));
+}